@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use warp::http::StatusCode;
+use warp::Filter;
+
+use crate::config::ConfigHandle;
+use crate::storage::StorageBackend;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AdminError {
+    #[error("missing or invalid bearer token")]
+    Unauthorized,
+
+    #[error("connection not found: {0}")]
+    ConnectionNotFound(String),
+
+    #[error("config reload failed: {0}")]
+    ReloadFailed(String),
+
+    #[error("storage error: {0}")]
+    Storage(String),
+}
+
+/// `/admin/services` 展示的服务摘要：service_id 及其声明的全部 action
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ServiceActions {
+    pub service_id: String,
+    pub actions: Vec<String>,
+}
+
+impl warp::reject::Reject for AdminError {}
+
+/// 一条被管理端点跟踪的活动连接记录（简化实现：没有真实传输层句柄，仅用于演示列出/断开操作）
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ConnectionRecord {
+    pub connection_id: String,
+    pub remote_addr: String,
+    pub connected_secs_ago: u64,
+}
+
+/// Admin API 的共享状态
+#[derive(Clone)]
+pub struct AdminState {
+    storage: Arc<dyn StorageBackend>,
+    config: ConfigHandle,
+    config_path: std::path::PathBuf,
+    connections: Arc<RwLock<HashMap<String, (String, std::time::Instant)>>>,
+}
+
+impl AdminState {
+    pub fn new(
+        storage: Arc<dyn StorageBackend>,
+        config: ConfigHandle,
+        config_path: std::path::PathBuf,
+    ) -> Self {
+        Self {
+            storage,
+            config,
+            config_path,
+            connections: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// 记录一条新建立的连接，供 /admin/connections 展示
+    pub async fn track_connection(&self, connection_id: String, remote_addr: String) {
+        self.connections
+            .write()
+            .await
+            .insert(connection_id, (remote_addr, std::time::Instant::now()));
+    }
+
+    async fn list_connections(&self) -> Vec<ConnectionRecord> {
+        self.connections
+            .read()
+            .await
+            .iter()
+            .map(|(id, (addr, since))| ConnectionRecord {
+                connection_id: id.clone(),
+                remote_addr: addr.clone(),
+                connected_secs_ago: since.elapsed().as_secs(),
+            })
+            .collect()
+    }
+
+    async fn drop_connection(&self, connection_id: &str) -> Result<(), AdminError> {
+        let mut connections = self.connections.write().await;
+        if connections.remove(connection_id).is_none() {
+            return Err(AdminError::ConnectionNotFound(connection_id.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// 校验 `Authorization: Bearer <token>` 是否匹配 `NFA_ADMIN_TOKEN`
+fn require_admin_token(
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("authorization").and_then(|header: Option<String>| async move {
+        let expected = std::env::var("NFA_ADMIN_TOKEN").unwrap_or_default();
+        let provided = header
+            .as_deref()
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .unwrap_or("");
+
+        if expected.is_empty() || provided != expected {
+            return Err(warp::reject::custom(AdminError::Unauthorized));
+        }
+        Ok(())
+    })
+    .untuple_one()
+}
+
+/// 组装管理 API 的全部路由，可与 `metrics_middleware` 一并挂载到同一个 warp 服务上
+pub fn admin_routes(
+    state: AdminState,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let base = warp::path("admin").and(require_admin_token());
+
+    let services = {
+        let state = state.clone();
+        base.clone()
+            .and(warp::path("services"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and_then(move || {
+                let state = state.clone();
+                async move { list_services_handler(state).await }
+            })
+            .boxed()
+    };
+
+    let connections_list = {
+        let state = state.clone();
+        base.clone()
+            .and(warp::path("connections"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and_then(move || {
+                let state = state.clone();
+                async move { list_connections_handler(state).await }
+            })
+            .boxed()
+    };
+
+    let connections_drop = {
+        let state = state.clone();
+        base.clone()
+            .and(warp::path("connections"))
+            .and(warp::path::param::<String>())
+            .and(warp::path::end())
+            .and(warp::delete())
+            .and_then(move |connection_id: String| {
+                let state = state.clone();
+                async move { drop_connection_handler(state, connection_id).await }
+            })
+            .boxed()
+    };
+
+    let config_get = {
+        let state = state.clone();
+        base.clone()
+            .and(warp::path("config"))
+            .and(warp::path::end())
+            .and(warp::get())
+            .and_then(move || {
+                let state = state.clone();
+                async move { get_config_handler(state).await }
+            })
+            .boxed()
+    };
+
+    let config_reload = {
+        let state = state.clone();
+        base.clone()
+            .and(warp::path("config"))
+            .and(warp::path("reload"))
+            .and(warp::path::end())
+            .and(warp::post())
+            .and_then(move || {
+                let state = state.clone();
+                async move { reload_config_handler(state).await }
+            })
+            .boxed()
+    };
+
+    services
+        .or(connections_list)
+        .or(connections_drop)
+        .or(config_get)
+        .or(config_reload)
+}
+
+/// 通过 `StorageBackend::get_all_service_ids`/`get_service` 列出服务及其声明的 action，
+/// 不依赖具体存储实现（Redis、Postgres 等均可），方便运维无需原生客户端即可巡检
+async fn list_services_handler(state: AdminState) -> Result<impl warp::Reply, warp::Rejection> {
+    let service_ids = state
+        .storage
+        .get_all_service_ids()
+        .await
+        .map_err(|e| warp::reject::custom(AdminError::Storage(e.to_string())))?;
+
+    let mut services = Vec::with_capacity(service_ids.len());
+    for service_id in service_ids {
+        let contract = state
+            .storage
+            .get_service(&service_id)
+            .await
+            .map_err(|e| warp::reject::custom(AdminError::Storage(e.to_string())))?;
+
+        if let Some(contract) = contract {
+            let actions = contract
+                .spec
+                .intent_patterns
+                .iter()
+                .map(|pattern| pattern.pattern.action.clone())
+                .collect();
+            services.push(ServiceActions { service_id, actions });
+        }
+    }
+
+    Ok(warp::reply::json(&services))
+}
+
+async fn list_connections_handler(state: AdminState) -> Result<impl warp::Reply, warp::Rejection> {
+    let connections = state.list_connections().await;
+    Ok(warp::reply::json(&connections))
+}
+
+async fn drop_connection_handler(
+    state: AdminState,
+    connection_id: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    state
+        .drop_connection(&connection_id)
+        .await
+        .map_err(warp::reject::custom)?;
+    Ok(warp::reply::with_status("dropped", StatusCode::OK))
+}
+
+async fn get_config_handler(state: AdminState) -> Result<impl warp::Reply, warp::Rejection> {
+    let config = state.config.get().await;
+    Ok(warp::reply::json(&*config))
+}
+
+async fn reload_config_handler(state: AdminState) -> Result<impl warp::Reply, warp::Rejection> {
+    // 复用与文件监视器相同的 apply_reload，确保两条路径都原子替换 ConfigHandle 并遵循
+    // restart_only_diff 语义，而不只是校验文件却不生效
+    crate::config::apply_reload(&state.config_path, &state.config)
+        .await
+        .map_err(|e| warp::reject::custom(AdminError::ReloadFailed(e.to_string())))?;
+
+    Ok(warp::reply::with_status("reloaded", StatusCode::OK))
+}
+
+/// 将 `AdminError` 映射为合适的 HTTP 状态码
+pub async fn handle_admin_rejection(
+    err: warp::Rejection,
+) -> Result<impl warp::Reply, std::convert::Infallible> {
+    let (status, message) = if let Some(e) = err.find::<AdminError>() {
+        match e {
+            AdminError::Unauthorized => (StatusCode::UNAUTHORIZED, e.to_string()),
+            AdminError::ConnectionNotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+            AdminError::ReloadFailed(_) => (StatusCode::BAD_REQUEST, e.to_string()),
+            AdminError::Storage(_) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        }
+    } else if err.is_not_found() {
+        (StatusCode::NOT_FOUND, "not found".to_string())
+    } else {
+        (StatusCode::INTERNAL_SERVER_ERROR, "internal error".to_string())
+    };
+
+    Ok(warp::reply::with_status(message, status))
+}
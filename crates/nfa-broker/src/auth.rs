@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use thiserror::Error;
+use tonic::metadata::MetadataMap;
+use tonic::Request;
+
+/// 身份校验失败的原因
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("missing credentials")]
+    MissingCredentials,
+
+    #[error("invalid or unknown credentials")]
+    InvalidCredentials,
+}
+
+/// 经过校验的调用方身份；`register_intent` 会将其绑定到新注册的 service_id 上，
+/// 后续 `heartbeat`/`unregister_intent` 据此判断调用方是否拥有该服务
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Identity(pub String);
+
+/// 某次 RPC 调用可用于身份校验的上下文：请求元数据 + （启用 mTLS 时的）客户端证书链 +
+/// （启用签名校验时的）被签名的请求负载
+#[derive(Debug, Clone)]
+pub struct AuthContext {
+    pub metadata: MetadataMap,
+    pub peer_certs: Option<std::sync::Arc<Vec<tonic::transport::Certificate>>>,
+    /// 调用方声称已签名的请求负载原始字节；仅在请求体本身可还原出确定性编码时由调用方填充
+    /// （如 `register_intent` 的契约），其余无请求体可签的方法（如 `heartbeat`）恒为 `None`
+    pub signed_payload: Option<Vec<u8>>,
+}
+
+/// 从 `Request` 中提取鉴权所需的上下文，供 `IdentityVerifier` 使用
+pub fn auth_context<T>(request: &Request<T>) -> AuthContext {
+    AuthContext {
+        metadata: request.metadata().clone(),
+        peer_certs: request.peer_certs(),
+        signed_payload: None,
+    }
+}
+
+impl AuthContext {
+    /// 附带被签名的请求负载，供 [`SignedKeypairVerifier`] 校验签名；用于请求体解码之后
+    /// （消费掉原始 `Request` 之后）才能得到规范化字节串的场景，如 `register_intent`
+    pub fn with_payload(mut self, payload: Vec<u8>) -> Self {
+        self.signed_payload = Some(payload);
+        self
+    }
+}
+
+/// 可插拔的身份校验器：部署方可选择静态令牌、CA 签发证书等后端
+pub trait IdentityVerifier: Send + Sync + std::fmt::Debug {
+    fn verify(&self, context: &AuthContext) -> Result<Identity, AuthError>;
+}
+
+/// 从 `authorization: Bearer <token>` 元数据中提取调用方令牌
+fn bearer_token(metadata: &MetadataMap) -> Option<&str> {
+    metadata
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+/// 基于静态令牌表的校验器：部署方在配置中声明 token -> identity 的映射
+#[derive(Debug, Default)]
+pub struct StaticTokenVerifier {
+    tokens: HashMap<String, Identity>,
+}
+
+impl StaticTokenVerifier {
+    pub fn new(tokens: HashMap<String, String>) -> Self {
+        Self {
+            tokens: tokens
+                .into_iter()
+                .map(|(token, identity)| (token, Identity(identity)))
+                .collect(),
+        }
+    }
+}
+
+impl IdentityVerifier for StaticTokenVerifier {
+    fn verify(&self, context: &AuthContext) -> Result<Identity, AuthError> {
+        let token = bearer_token(&context.metadata).ok_or(AuthError::MissingCredentials)?;
+        self.tokens
+            .get(token)
+            .cloned()
+            .ok_or(AuthError::InvalidCredentials)
+    }
+}
+
+/// 基于 mTLS 客户端证书的校验器（简化实现：只校验连接是否携带了客户端证书，
+/// 并将所有已验证连接绑定到同一个部署级身份；生产环境应改为按证书 CN/SAN 做逐证书身份映射，
+/// 需要由受信 CA 签发证书链并在传输层启用 `client_ca_root`）
+#[derive(Debug, Clone)]
+pub struct CertificateVerifier {
+    identity: Identity,
+}
+
+impl CertificateVerifier {
+    pub fn new(identity: impl Into<String>) -> Self {
+        Self {
+            identity: Identity(identity.into()),
+        }
+    }
+}
+
+impl IdentityVerifier for CertificateVerifier {
+    fn verify(&self, context: &AuthContext) -> Result<Identity, AuthError> {
+        match &context.peer_certs {
+            Some(certs) if !certs.is_empty() => Ok(self.identity.clone()),
+            _ => Err(AuthError::MissingCredentials),
+        }
+    }
+}
+
+/// 基于 Curve25519 (Ed25519) 密钥对的校验器：调用方在 `x-nfa-pubkey`/`x-nfa-signature`
+/// 元数据中携带公钥与对请求负载的签名，身份即为公钥本身的十六进制表示，从而天然按公钥
+/// 区分不同调用方的归属，无需预先下发令牌
+#[derive(Debug, Clone, Default)]
+pub struct SignedKeypairVerifier;
+
+impl IdentityVerifier for SignedKeypairVerifier {
+    fn verify(&self, context: &AuthContext) -> Result<Identity, AuthError> {
+        let pubkey_hex = metadata_str(&context.metadata, "x-nfa-pubkey")
+            .ok_or(AuthError::MissingCredentials)?;
+        let signature_hex = metadata_str(&context.metadata, "x-nfa-signature")
+            .ok_or(AuthError::MissingCredentials)?;
+        let payload = context
+            .signed_payload
+            .as_deref()
+            .ok_or(AuthError::MissingCredentials)?;
+
+        crate::identity::verify_signature(pubkey_hex, payload, signature_hex)
+            .map_err(|_| AuthError::InvalidCredentials)?;
+
+        Ok(Identity(pubkey_hex.to_string()))
+    }
+}
+
+/// 从元数据中读取一个纯文本字符串字段
+fn metadata_str<'a>(metadata: &'a MetadataMap, key: &str) -> Option<&'a str> {
+    metadata.get(key).and_then(|value| value.to_str().ok())
+}
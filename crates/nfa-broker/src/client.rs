@@ -1,4 +1,6 @@
-use tonic::transport::Channel;
+use std::time::Duration;
+
+use tonic::transport::{Channel, Endpoint};
 use tonic::Request;
 use nfa_common::intent::{IntentRequest, IntentResponse};
 use nfa_idl::IntentContract;
@@ -17,42 +19,165 @@ use nfa::intent::v1alpha::{IntentPattern, IntentContext};
 pub enum BrokerClientError {
     #[error("gRPC transport error: {0}")]
     Transport(#[from] tonic::transport::Error),
-    
+
     #[error("gRPC status error: {0}")]
     Status(#[from] tonic::Status),
-    
+
     #[error("Serialization error: {0}")]
     Serialization(String),
+
+    #[error("call timed out after {0:?}")]
+    Timeout(Duration),
+
+    #[error("no broker endpoints configured")]
+    NoEndpoints,
+}
+
+/// 重试策略：最大重试次数、指数退避的基准/上限间隔、单次调用超时。
+/// 每次重试都会对负载均衡 channel 中的其余健康端点重新发起调用，
+/// 从而在 broker 重启或短暂失联期间保持透明重连
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_backoff: Duration,
+    pub max_backoff: Duration,
+    pub call_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            call_timeout: Duration::from_secs(10),
+        }
+    }
 }
 
-/// Broker客户端
+impl RetryPolicy {
+    fn backoff_for(&self, attempt: u32) -> Duration {
+        let exp = self.base_backoff.saturating_mul(2u32.saturating_pow(attempt));
+        exp.min(self.max_backoff)
+    }
+}
+
+/// Broker客户端：基于负载均衡 channel 连接多个端点，并在瞬时故障下自动重连重试
 pub struct BrokerClient {
     client: IntentBrokerClient<Channel>,
+    retry_policy: RetryPolicy,
+    auth_token: Option<String>,
+    identity_key: Option<crate::identity::Keypair>,
 }
 
 impl BrokerClient {
-    /// 连接到Broker
+    /// 连接到单个Broker，等价于 `connect_many` 传入单元素列表
     pub async fn connect(addr: String) -> Result<Self, BrokerClientError> {
-        let client = IntentBrokerClient::connect(addr).await?;
-        Ok(Self { client })
+        Self::connect_many(vec![addr]).await
+    }
+
+    /// 连接到多个Broker端点，构建一个负载均衡的 channel；任一端点失联时，
+    /// 流量由 tonic 自动转移到其余健康端点
+    pub async fn connect_many(addrs: Vec<String>) -> Result<Self, BrokerClientError> {
+        if addrs.is_empty() {
+            return Err(BrokerClientError::NoEndpoints);
+        }
+
+        let endpoints: Vec<Endpoint> = addrs
+            .into_iter()
+            .map(Endpoint::from_shared)
+            .collect::<Result<_, _>>()?;
+
+        let channel = Channel::balance_list(endpoints.into_iter());
+
+        Ok(Self {
+            client: IntentBrokerClient::new(channel),
+            retry_policy: RetryPolicy::default(),
+            auth_token: None,
+            identity_key: None,
+        })
+    }
+
+    /// 配置最大重试次数
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.retry_policy.max_retries = max_retries;
+        self
+    }
+
+    /// 配置指数退避的基准间隔
+    pub fn with_base_backoff(mut self, base_backoff: Duration) -> Self {
+        self.retry_policy.base_backoff = base_backoff;
+        self
+    }
+
+    /// 配置指数退避的上限间隔
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.retry_policy.max_backoff = max_backoff;
+        self
+    }
+
+    /// 配置单次调用超时
+    pub fn with_call_timeout(mut self, call_timeout: Duration) -> Self {
+        self.retry_policy.call_timeout = call_timeout;
+        self
+    }
+
+    /// 配置鉴权令牌，后续每次 RPC 调用都会携带 `authorization: Bearer <token>` 元数据，
+    /// 与服务端的 `IdentityVerifier` 配对使用
+    pub fn with_auth_token(mut self, token: impl Into<String>) -> Self {
+        self.auth_token = Some(token.into());
+        self
+    }
+
+    /// 配置客户端身份密钥，后续 `register_intent` 会用它对契约签名并附带公钥，
+    /// 证明调用方持有该密钥对应的私钥，服务端据此将 [`crate::auth::SignedKeypairVerifier`]
+    /// 校验出的公钥记为该服务的 owner
+    pub fn with_identity_key(mut self, key: crate::identity::Keypair) -> Self {
+        self.identity_key = Some(key);
+        self
     }
-    
+
     /// 注册意图服务
     pub async fn register_intent(
         &mut self,
         contract: IntentContract,
     ) -> Result<RegisterIntentResponse, BrokerClientError> {
-        // 转换为protobuf格式
         let proto_contract = self.contract_to_proto(contract)?;
-        
-        let request = Request::new(RegisterIntentRequest {
-            contract: Some(proto_contract),
-        });
-        
-        let response = self.client.register_intent(request).await?;
-        Ok(response.into_inner())
+
+        // 签名须覆盖服务端用于校验的同一份字节，即这份 proto 编码本身，而非内部契约类型的
+        // JSON 序列化——proto⇄内部契约的转换目前是有损的简化实现，双方唯一能确定性复现的
+        // 编码就是发送上线的 proto 字节
+        let signature = self
+            .identity_key
+            .as_ref()
+            .map(|key| -> Result<(String, String), BrokerClientError> {
+                let payload = prost::Message::encode_to_vec(&proto_contract);
+                Ok((key.public_key_hex(), key.sign_hex(&payload)))
+            })
+            .transpose()?;
+
+        let policy = self.retry_policy.clone();
+        let auth_token = self.auth_token.clone();
+        let client = &mut self.client;
+
+        retry_call(&policy, move || {
+            let mut request = Request::new(RegisterIntentRequest {
+                contract: Some(proto_contract.clone()),
+            });
+            attach_auth(&mut request, &auth_token);
+            if let Some((pubkey_hex, signature_hex)) = &signature {
+                if let Ok(value) = pubkey_hex.parse() {
+                    request.metadata_mut().insert("x-nfa-pubkey", value);
+                }
+                if let Ok(value) = signature_hex.parse() {
+                    request.metadata_mut().insert("x-nfa-signature", value);
+                }
+            }
+            client.register_intent(request)
+        })
+        .await
     }
-    
+
     /// 匹配意图
     pub async fn match_intent(
         &mut self,
@@ -61,38 +186,79 @@ impl BrokerClient {
     ) -> Result<IntentMatchResponse, BrokerClientError> {
         let proto_pattern = self.pattern_to_proto(pattern)?;
         let proto_context = context.map(|c| self.context_to_proto(c));
-        
-        let request = Request::new(IntentMatchRequest {
-            pattern: Some(proto_pattern),
-            context: proto_context,
-        });
-        
-        let response = self.client.match_intent(request).await?;
-        Ok(response.into_inner())
+        let policy = self.retry_policy.clone();
+        let auth_token = self.auth_token.clone();
+        let client = &mut self.client;
+
+        retry_call(&policy, move || {
+            let mut request = Request::new(IntentMatchRequest {
+                pattern: Some(proto_pattern.clone()),
+                context: proto_context.clone(),
+            });
+            attach_auth(&mut request, &auth_token);
+            client.match_intent(request)
+        })
+        .await
     }
-    
+
     /// 发送心跳
     pub async fn heartbeat(
         &mut self,
         service_id: String,
     ) -> Result<HeartbeatResponse, BrokerClientError> {
-        let request = Request::new(HeartbeatRequest { service_id });
-        
-        let response = self.client.heartbeat(request).await?;
+        let policy = self.retry_policy.clone();
+        let auth_token = self.auth_token.clone();
+        let client = &mut self.client;
+
+        retry_call(&policy, move || {
+            let mut request = Request::new(HeartbeatRequest {
+                service_id: service_id.clone(),
+            });
+            attach_auth(&mut request, &auth_token);
+            client.heartbeat(request)
+        })
+        .await
+    }
+
+    /// 订阅 `pattern` 对应 action 的注册/注销事件；返回的流会在每次变化时推送最新的匹配快照，
+    /// 免去长期运行的 agent 反复轮询 `match_intent`
+    pub async fn watch_intent(
+        &mut self,
+        pattern: IntentPattern,
+        context: Option<IntentContext>,
+    ) -> Result<tonic::Streaming<IntentMatchResponse>, BrokerClientError> {
+        let proto_pattern = self.pattern_to_proto(pattern)?;
+        let proto_context = context.map(|c| self.context_to_proto(c));
+
+        let mut request = Request::new(IntentMatchRequest {
+            pattern: Some(proto_pattern),
+            context: proto_context,
+        });
+        attach_auth(&mut request, &self.auth_token);
+
+        let response = self.client.watch_intent(request).await?;
         Ok(response.into_inner())
     }
-    
+
     /// 取消注册服务
     pub async fn unregister_intent(
         &mut self,
         service_id: String,
     ) -> Result<UnregisterIntentResponse, BrokerClientError> {
-        let request = Request::new(UnregisterIntentRequest { service_id });
-        
-        let response = self.client.unregister_intent(request).await?;
-        Ok(response.into_inner())
+        let policy = self.retry_policy.clone();
+        let auth_token = self.auth_token.clone();
+        let client = &mut self.client;
+
+        retry_call(&policy, move || {
+            let mut request = Request::new(UnregisterIntentRequest {
+                service_id: service_id.clone(),
+            });
+            attach_auth(&mut request, &auth_token);
+            client.unregister_intent(request)
+        })
+        .await
     }
-    
+
     /// 将内部契约转换为protobuf格式
     fn contract_to_proto(&self, contract: IntentContract) -> Result<nfa::intent::v1alpha::IntentContract, BrokerClientError> {
         // 简化实现，实际中需要完整转换
@@ -126,7 +292,7 @@ impl BrokerClient {
             }),
         })
     }
-    
+
     /// 将内部模式转换为protobuf格式
     fn pattern_to_proto(&self, pattern: nfa_common::intent::IntentPattern) -> Result<nfa::intent::v1alpha::IntentPattern, BrokerClientError> {
         // 简化实现
@@ -138,7 +304,7 @@ impl BrokerClient {
             constraints: None,
         })
     }
-    
+
     /// 将内部上下文转换为protobuf格式
     fn context_to_proto(&self, context: nfa_common::intent::IntentContext) -> nfa::intent::v1alpha::IntentContext {
         nfa::intent::v1alpha::IntentContext {
@@ -148,4 +314,48 @@ impl BrokerClient {
             preferences: std::collections::HashMap::new(), // 需要完整转换
         }
     }
-}
\ No newline at end of file
+}
+
+/// 以 `policy` 为重试策略执行一次 RPC 调用；对瞬时传输/状态错误按指数退避重试，
+/// 每次尝试受 `call_timeout` 限制，仅 `Unavailable`/`DeadlineExceeded` 视为可重试
+async fn retry_call<T, Fut>(
+    policy: &RetryPolicy,
+    mut attempt: impl FnMut() -> Fut,
+) -> Result<T, BrokerClientError>
+where
+    Fut: std::future::Future<Output = Result<tonic::Response<T>, tonic::Status>>,
+{
+    let mut tries = 0;
+    loop {
+        match tokio::time::timeout(policy.call_timeout, attempt()).await {
+            Ok(Ok(response)) => return Ok(response.into_inner()),
+            Ok(Err(status)) if tries < policy.max_retries && is_retryable_status(&status) => {
+                tokio::time::sleep(policy.backoff_for(tries)).await;
+                tries += 1;
+            }
+            Ok(Err(status)) => return Err(BrokerClientError::Status(status)),
+            Err(_) if tries < policy.max_retries => {
+                tokio::time::sleep(policy.backoff_for(tries)).await;
+                tries += 1;
+            }
+            Err(_) => return Err(BrokerClientError::Timeout(policy.call_timeout)),
+        }
+    }
+}
+
+/// 仅瞬时故障（服务不可用/调用超时）才值得重试，其余 Status 码直接返回给调用方
+fn is_retryable_status(status: &tonic::Status) -> bool {
+    matches!(
+        status.code(),
+        tonic::Code::Unavailable | tonic::Code::DeadlineExceeded
+    )
+}
+
+/// 配置了 `with_auth_token` 时，为请求附加 `authorization: Bearer <token>` 元数据
+fn attach_auth<T>(request: &mut Request<T>, token: &Option<String>) {
+    if let Some(token) = token {
+        if let Ok(value) = format!("Bearer {token}").parse() {
+            request.metadata_mut().insert("authorization", value);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::service::BrokerService;
+
+#[derive(Debug, thiserror::Error)]
+pub enum HealthError {
+    #[error("ntp query failed for {server}: {reason}")]
+    NtpQueryFailed { server: String, reason: String },
+}
+
+/// 服务健康状态机：错过一次心跳周期进入 Suspect，连续错过达到阈值后判定 Unhealthy，
+/// 替代原先仅凭 `last_heartbeat.elapsed()` 单一阈值判断的布尔值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum HealthState {
+    Healthy,
+    Suspect,
+    Unhealthy,
+}
+
+impl Default for HealthState {
+    fn default() -> Self {
+        HealthState::Healthy
+    }
+}
+
+/// 周期性查询 NTP 服务器计算本地时钟偏移，并驱动 `BrokerService` 中各服务的心跳状态机；
+/// 偏移超出容忍范围时将 broker 标记为降级，并放宽心跳过期的判定容忍度，避免误判
+pub struct HealthMonitor {
+    ntp_servers: Vec<String>,
+    /// 时钟偏移超过该值（毫秒）时认为 broker 处于降级状态
+    max_clock_drift_ms: i64,
+    /// 连续错过心跳达到该次数才判定为 Unhealthy（第一次错过即进入 Suspect）
+    unhealthy_after_missed_beats: u32,
+    clock_drift_ms: Arc<AtomicI64>,
+    degraded: Arc<AtomicBool>,
+}
+
+impl HealthMonitor {
+    pub fn new(ntp_servers: Vec<String>, max_clock_drift_ms: i64, unhealthy_after_missed_beats: u32) -> Self {
+        Self {
+            ntp_servers,
+            max_clock_drift_ms,
+            unhealthy_after_missed_beats,
+            clock_drift_ms: Arc::new(AtomicI64::new(0)),
+            degraded: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 最近一次测得的本地时钟偏移（毫秒，正值表示本地时钟偏慢，即落后于 NTP 服务器）
+    pub fn clock_drift_ms(&self) -> i64 {
+        self.clock_drift_ms.load(Ordering::Relaxed)
+    }
+
+    /// broker 是否因测得的时钟漂移超出容忍范围而处于降级状态
+    pub fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::Relaxed)
+    }
+
+    /// 启动两个后台任务：一个周期性刷新 NTP 时钟偏移，一个按 `heartbeat_interval` 驱动
+    /// 服务健康状态机；降级时心跳容忍间隔会加倍，避免时钟漂移导致的误判过期
+    pub fn spawn(self: Arc<Self>, service: BrokerService, ntp_poll_interval: Duration, heartbeat_interval: Duration) {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(ntp_poll_interval);
+            loop {
+                interval.tick().await;
+                monitor.refresh_clock_offset().await;
+            }
+        });
+
+        let monitor = self;
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(heartbeat_interval);
+            loop {
+                interval.tick().await;
+                let tolerance = if monitor.is_degraded() {
+                    heartbeat_interval * 2
+                } else {
+                    heartbeat_interval
+                };
+                service
+                    .update_health_states(tolerance, monitor.unhealthy_after_missed_beats)
+                    .await;
+            }
+        });
+    }
+
+    /// 查询全部配置的 NTP 服务器，取偏移中位数以减小单一服务器异常的影响
+    async fn refresh_clock_offset(&self) {
+        if self.ntp_servers.is_empty() {
+            return;
+        }
+
+        let queries = self.ntp_servers.iter().cloned().map(|server| {
+            tokio::task::spawn_blocking(move || query_offset_ms(&server))
+        });
+
+        let mut offsets = Vec::new();
+        for query in queries {
+            match query.await {
+                Ok(Ok(offset)) => offsets.push(offset),
+                Ok(Err(e)) => tracing::warn!("NTP query failed: {}", e),
+                Err(e) => tracing::warn!("NTP query task panicked: {}", e),
+            }
+        }
+
+        if offsets.is_empty() {
+            return;
+        }
+
+        offsets.sort_unstable();
+        let median = offsets[offsets.len() / 2];
+
+        self.clock_drift_ms.store(median, Ordering::Relaxed);
+        self.degraded
+            .store(median.abs() > self.max_clock_drift_ms, Ordering::Relaxed);
+    }
+}
+
+const NTP_EPOCH_OFFSET_SECS: f64 = 2_208_988_800.0; // 1900-01-01 到 1970-01-01 的秒数
+const NTP_FRACTION_PER_SEC: f64 = 4_294_967_296.0; // 2^32，NTP 定点小数部分的分母
+
+/// 最小化的 SNTP v3 客户端（简化实现：不校验 stratum/root dispersion），
+/// 按标准 SNTP 偏移公式 `((T2 - T1) + (T3 - T4)) / 2` 计算本地时钟偏移，保留毫秒级精度
+fn query_offset_ms(server: &str) -> Result<i64, HealthError> {
+    let to_err = |reason: std::io::Error| HealthError::NtpQueryFailed {
+        server: server.to_string(),
+        reason: reason.to_string(),
+    };
+
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(to_err)?;
+    socket
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .map_err(to_err)?;
+
+    let mut packet = [0u8; 48];
+    packet[0] = 0b00_011_011; // LI = 0, VN = 3, Mode = 3 (client)
+
+    let t1 = unix_time_to_ntp_secs(SystemTime::now());
+    socket.send_to(&packet, (server, 123)).map_err(to_err)?;
+
+    let mut response = [0u8; 48];
+    socket.recv_from(&mut response).map_err(to_err)?;
+    let t4 = unix_time_to_ntp_secs(SystemTime::now());
+
+    // 响应报文中接收时间戳位于字节 32..40（T2），发送时间戳位于字节 40..48（T3）；
+    // 各自由 32 位整数秒 + 32 位小数部分（fraction/2^32）组成，需要一并解析才能得到亚秒精度
+    let t2 = be_ntp_timestamp(&response[32..40]);
+    let t3 = be_ntp_timestamp(&response[40..48]);
+
+    let offset_secs = ((t2 - t1) + (t3 - t4)) / 2.0;
+    Ok((offset_secs * 1000.0).round() as i64)
+}
+
+/// 将本地 `SystemTime` 转换为 NTP 时间戳（自 1900-01-01 起的秒数，含小数部分），
+/// 与 `be_ntp_timestamp` 解析出的远端时间戳保持同等精度，使偏移计算不会被整秒截断
+fn unix_time_to_ntp_secs(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64() + NTP_EPOCH_OFFSET_SECS
+}
+
+/// 解析一个 64 位 NTP 定点时间戳（高 32 位整数秒 + 低 32 位小数部分）为含小数的秒数
+fn be_ntp_timestamp(bytes: &[u8]) -> f64 {
+    let secs = be_u32(&bytes[0..4]) as f64;
+    let frac = be_u32(&bytes[4..8]) as f64 / NTP_FRACTION_PER_SEC;
+    secs + frac
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
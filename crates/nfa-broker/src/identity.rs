@@ -0,0 +1,81 @@
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IdentityError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid hex encoding: {0}")]
+    Hex(#[from] hex::FromHexError),
+
+    #[error("malformed key material")]
+    MalformedKey,
+
+    #[error("signature verification failed")]
+    InvalidSignature,
+}
+
+/// 基于 Curve25519 (Ed25519) 的客户端/Broker 身份密钥对；`nfa keygen` 生成并落盘，
+/// 之后 `register_intent` 用它对契约签名，broker 用公钥校验调用方身份
+pub struct Keypair {
+    signing_key: SigningKey,
+}
+
+impl Keypair {
+    /// 生成一个新的随机密钥对
+    pub fn generate() -> Self {
+        let mut csprng = rand::rngs::OsRng;
+        Self {
+            signing_key: SigningKey::generate(&mut csprng),
+        }
+    }
+
+    /// 将密钥对写入文件（32 字节原始私钥），路径不存在的父目录会被自动创建；
+    /// 不额外设置文件权限，新文件沿用进程默认的 umask
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), IdentityError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.signing_key.to_bytes())?;
+        Ok(())
+    }
+
+    /// 从文件加载此前由 `save_to_file` 写入的密钥对
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, IdentityError> {
+        let bytes = std::fs::read(path)?;
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| IdentityError::MalformedKey)?;
+        Ok(Self {
+            signing_key: SigningKey::from_bytes(&bytes),
+        })
+    }
+
+    /// 公钥的十六进制表示，同时也是 broker 记录的调用方身份标识
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.signing_key.verifying_key().to_bytes())
+    }
+
+    /// 对任意字节串签名，返回签名的十六进制表示
+    pub fn sign_hex(&self, message: &[u8]) -> String {
+        hex::encode(self.signing_key.sign(message).to_bytes())
+    }
+}
+
+/// 校验 `message` 的签名是否匹配 `pubkey_hex` 对应的公钥；用于 broker 端验证客户端身份证明
+pub fn verify_signature(pubkey_hex: &str, message: &[u8], signature_hex: &str) -> Result<(), IdentityError> {
+    let pubkey_bytes: [u8; 32] = hex::decode(pubkey_hex)?
+        .try_into()
+        .map_err(|_| IdentityError::MalformedKey)?;
+    let verifying_key = VerifyingKey::from_bytes(&pubkey_bytes).map_err(|_| IdentityError::MalformedKey)?;
+
+    let signature_bytes: [u8; 64] = hex::decode(signature_hex)?
+        .try_into()
+        .map_err(|_| IdentityError::MalformedKey)?;
+    let signature = Signature::from_bytes(&signature_bytes);
+
+    verifying_key
+        .verify(message, &signature)
+        .map_err(|_| IdentityError::InvalidSignature)
+}
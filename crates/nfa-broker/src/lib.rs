@@ -8,9 +8,28 @@ use tonic::{transport::Server, Request, Response, Status};
 mod service;
 pub use service::BrokerService;
 
+pub mod admin;
+pub mod auth;
+pub mod client;
+pub mod config;
+pub mod health;
+pub mod identity;
+pub mod matching;
+pub mod metrics;
+pub mod storage;
+pub mod ws;
+pub mod ws_client;
+
 pub struct Broker {
     address: String,
     service: BrokerService,
+    heartbeat_ttl: std::time::Duration,
+    ntp_servers: Vec<String>,
+    ntp_poll_interval: std::time::Duration,
+    max_clock_drift_ms: i64,
+    unhealthy_after_missed_beats: u32,
+    ws_listen_address: Option<String>,
+    identity_key: Option<identity::Keypair>,
 }
 
 impl Broker {
@@ -19,21 +38,133 @@ impl Broker {
         Ok(Self {
             address: address.to_string(),
             service,
+            heartbeat_ttl: std::time::Duration::from_secs(30),
+            ntp_servers: Vec::new(),
+            ntp_poll_interval: std::time::Duration::from_secs(60),
+            max_clock_drift_ms: 200,
+            unhealthy_after_missed_beats: 3,
+            ws_listen_address: None,
+            identity_key: None,
         })
     }
-    
+
+    /// 配置心跳 TTL，后台清理任务据此判断服务是否失联
+    pub fn with_heartbeat_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.heartbeat_ttl = ttl;
+        self
+    }
+
+    /// 配置身份校验器，开启后 `register_intent` 绑定的身份将用于 `unregister_intent`/`heartbeat`
+    /// 的归属校验；未配置时鉴权保持关闭（向后兼容），任何调用方均可操作任意 service_id
+    pub fn with_auth_verifier(mut self, verifier: Arc<dyn auth::IdentityVerifier>) -> Self {
+        self.service.set_verifier(verifier);
+        self
+    }
+
+    /// 配置用于时钟偏移校准的 NTP 服务器列表；留空（默认）则完全跳过时钟漂移检测，
+    /// broker 永不进入降级状态
+    pub fn with_ntp_servers(mut self, servers: Vec<String>) -> Self {
+        self.ntp_servers = servers;
+        self
+    }
+
+    /// 配置 NTP 轮询间隔，默认 60 秒
+    pub fn with_ntp_poll_interval(mut self, interval: std::time::Duration) -> Self {
+        self.ntp_poll_interval = interval;
+        self
+    }
+
+    /// 配置本地时钟偏移超过该值（毫秒）时将 broker 标记为降级，默认 200ms
+    pub fn with_max_clock_drift_ms(mut self, max_drift_ms: i64) -> Self {
+        self.max_clock_drift_ms = max_drift_ms;
+        self
+    }
+
+    /// 配置连续错过多少次心跳周期后将服务判定为 Unhealthy，默认 3 次
+    pub fn with_unhealthy_after_missed_beats(mut self, missed_beats: u32) -> Self {
+        self.unhealthy_after_missed_beats = missed_beats;
+        self
+    }
+
+    /// 配置持久化存储后端，开启后 `register_intent`/`unregister_intent` 会写穿到该存储，
+    /// `run()` 启动时会先从中恢复此前注册的服务；未配置时注册表纯存于内存（向后兼容）
+    pub fn with_storage_backend(mut self, backend: Arc<dyn storage::StorageBackend>) -> Self {
+        self.service.set_storage(backend);
+        self
+    }
+
+    /// 额外在该地址上接受 WebSocket 连接，与 gRPC server 并行提供注册/匹配/查询能力，
+    /// 供浏览器或无法使用 HTTP/2 的客户端接入；未配置（默认）时只提供 gRPC
+    pub fn with_ws_listen_address(mut self, addr: impl Into<String>) -> Self {
+        self.ws_listen_address = Some(addr.into());
+        self
+    }
+
+    /// 配置 broker 自身的静态身份密钥；客户端可预先固定（pin）该公钥以确认连接的对端身份。
+    /// 目前仅用于公开该公钥供客户端核对，尚未实现基于 Noise 协议的信道加密（后续工作）
+    pub fn with_identity_key(mut self, key: identity::Keypair) -> Self {
+        self.identity_key = Some(key);
+        self
+    }
+
     pub async fn run(self) -> Result<(), BrokerError> {
         let addr = self.address.parse().expect("invalid address");
-        let service = self.service;
-        
+        let mut service = self.service;
+        let heartbeat_ttl = self.heartbeat_ttl;
+        // 须在 service 被 clone 分发给后台任务（reaper/health monitor）之前完成配置，
+        // 使 heartbeat/register_intent 写穿 storage 时能据此设置存活键的 TTL
+        service.set_heartbeat_ttl(heartbeat_ttl);
+
         info!("Broker listening on {}", addr);
-        
+
+        if let Some(key) = &self.identity_key {
+            tracing::info!("Broker identity public key: {}", key.public_key_hex());
+        }
+
+        // 存在持久化存储时，先恢复重启前注册的服务，再开始接受流量
+        service
+            .rehydrate()
+            .await
+            .map_err(|e| BrokerError::StorageError(e.to_string()))?;
+
+        // 后台任务：定期清理超过心跳 TTL 未续约的服务，避免失联端点滞留在模式索引中
+        let reaper_service = service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(heartbeat_ttl);
+            loop {
+                interval.tick().await;
+                let expired = reaper_service.reap_expired(heartbeat_ttl).await;
+                if !expired.is_empty() {
+                    info!("Reaped {} expired service(s): {:?}", expired.len(), expired);
+                }
+                crate::metrics::SERVICES_REGISTERED.set(reaper_service.service_count().await as f64);
+            }
+        });
+
+        // 后台任务：NTP 时钟漂移校准 + 心跳健康状态机，替代原先单一的 30 秒布尔阈值判断
+        let monitor = Arc::new(health::HealthMonitor::new(
+            self.ntp_servers,
+            self.max_clock_drift_ms,
+            self.unhealthy_after_missed_beats,
+        ));
+        monitor.spawn(service.clone(), self.ntp_poll_interval, heartbeat_ttl);
+
+        // 可选的 WebSocket 传输：与 gRPC server 并行监听，供无法使用 HTTP/2 的客户端接入
+        if let Some(ws_addr) = self.ws_listen_address {
+            let ws_service = service.clone();
+            tokio::spawn(async move {
+                if let Err(e) = ws::serve(ws_service, ws_addr).await {
+                    tracing::error!("WebSocket transport stopped: {}", e);
+                }
+            });
+        }
+
         Server::builder()
             .add_service(BrokerService::server(service))
             .serve(addr)
             .await
             .map_err(|e| BrokerError::ServerError(e.to_string()))?;
-            
+
         Ok(())
     }
 }
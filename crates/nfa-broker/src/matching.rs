@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+
+use nfa_common::intent::{IntentPattern, IntentRequest, ParameterConstraint};
+use nfa_idl::IntentContract;
+
+/// 一次匹配计算得到的候选服务及其得分，得分越高表示声明的约束满足程度越高
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchCandidate {
+    pub service_id: String,
+    pub score: f64,
+}
+
+/// 在候选服务中按 `request.action` 精确匹配后，对每个候选的意图模式做约束校验并打分，
+/// 丢弃任何未满足硬性约束（`required_parameters` 缺失）的候选，其余按得分降序返回
+pub fn match_candidates<'a, I>(request: &IntentRequest, candidates: I) -> Vec<MatchCandidate>
+where
+    I: IntoIterator<Item = (&'a String, &'a IntentContract)>,
+{
+    let mut ranked: Vec<MatchCandidate> = candidates
+        .into_iter()
+        .filter_map(|(service_id, contract)| {
+            contract
+                .spec
+                .intent_patterns
+                .iter()
+                .filter(|pattern| pattern.pattern.action == request.action)
+                .filter_map(|pattern| score_pattern(pattern, &request.parameters))
+                .fold(None, |best: Option<f64>, score| {
+                    Some(best.map_or(score, |b| b.max(score)))
+                })
+                .map(|score| MatchCandidate {
+                    service_id: service_id.clone(),
+                    score,
+                })
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
+}
+
+/// 对单个意图模式打分；返回 `None` 表示命中了硬性约束（缺少必需参数），候选应被整体丢弃
+fn score_pattern(
+    pattern: &IntentPattern,
+    request_params: &HashMap<String, serde_json::Value>,
+) -> Option<f64> {
+    let Some(constraints) = &pattern.constraints else {
+        // 未声明约束，视为完全匹配
+        return Some(1.0);
+    };
+
+    let required = constraints
+        .required_parameters
+        .as_deref()
+        .unwrap_or(&[]);
+    for name in required {
+        if !request_params.contains_key(name) {
+            return None;
+        }
+    }
+
+    let parameter_constraints = constraints.parameter_constraints.as_ref();
+    let mut satisfied = required.len();
+    let mut declared = required.len();
+
+    if let Some(parameter_constraints) = parameter_constraints {
+        declared += parameter_constraints.len();
+        for (name, constraint) in parameter_constraints {
+            if let Some(value) = request_params.get(name) {
+                if constraint_satisfied(value, constraint) {
+                    satisfied += 1;
+                }
+            }
+        }
+    }
+
+    let mut score = if declared == 0 {
+        1.0
+    } else {
+        satisfied as f64 / declared as f64
+    };
+
+    // 额外奖励：请求携带了该模式声明的、非必需的参数，说明请求与服务声明的重合度更高
+    let optional_declared: Vec<&String> = pattern
+        .pattern
+        .parameters
+        .keys()
+        .filter(|name| !required.contains(name))
+        .collect();
+    if !optional_declared.is_empty() {
+        let provided = optional_declared
+            .iter()
+            .filter(|name| request_params.contains_key(name.as_str()))
+            .count();
+        score += 0.1 * (provided as f64 / optional_declared.len() as f64);
+    }
+
+    Some(score)
+}
+
+/// 校验单个参数值是否满足其约束（类型、枚举取值、数值范围）
+fn constraint_satisfied(value: &serde_json::Value, constraint: &ParameterConstraint) -> bool {
+    if let Some(expected_type) = &constraint.r#type {
+        let type_matches = match expected_type.as_str() {
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            _ => true,
+        };
+        if !type_matches {
+            return false;
+        }
+    }
+
+    if let Some(enum_values) = &constraint.enum_values {
+        match value.as_str() {
+            Some(s) if enum_values.iter().any(|v| v == s) => {}
+            _ => return false,
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(min) = constraint.min {
+            if number < min {
+                return false;
+            }
+        }
+        if let Some(max) = constraint.max {
+            if number > max {
+                return false;
+            }
+        }
+    }
+
+    true
+}
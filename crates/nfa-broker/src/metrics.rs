@@ -1,53 +1,296 @@
 use lazy_static::lazy_static;
 use prometheus::{
-    register_counter, register_gauge, register_histogram,
-    Counter, Gauge, Histogram, Encoder, TextEncoder,
+    register_counter_vec, register_gauge, register_histogram_vec,
+    CounterVec, Gauge, HistogramVec, HistogramOpts, Encoder, TextEncoder,
 };
 use std::time::Instant;
 use tokio::task;
 use warp::Filter;
 
+/// 指标子系统配置：直方图桶边界 + 导出方式
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MetricsConfig {
+    /// `REQUEST_DURATION` 直方图的桶边界（秒），按延迟 SLO 调整
+    #[serde(default = "default_histogram_buckets")]
+    pub histogram_buckets: Vec<f64>,
+    /// 指标导出方式
+    #[serde(default)]
+    pub exporter: MetricsExporter,
+}
+
+fn default_histogram_buckets() -> Vec<f64> {
+    // 覆盖从亚毫秒到数秒的常见延迟分布，而非默认的单一分桶
+    vec![
+        0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+    ]
+}
+
+/// 指标导出方式
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MetricsExporter {
+    /// 由 Prometheus 主动拉取，当前默认行为
+    Prometheus { path: String },
+    /// 定期将已采集的指标族推送到 Pushgateway（短生命周期/NAT 后实例）
+    Pushgateway {
+        url: String,
+        job: String,
+        interval_secs: u64,
+    },
+    /// 通过 OTLP 定期推送指标（尚未实现，见 [`push_to_otlp`]；配置后会按 `interval_secs`
+    /// 周期性记录导出失败的告警，而不会假装导出成功）
+    Otlp { endpoint: String, interval_secs: u64 },
+}
+
+impl Default for MetricsExporter {
+    fn default() -> Self {
+        MetricsExporter::Prometheus {
+            path: "/metrics".to_string(),
+        }
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            histogram_buckets: default_histogram_buckets(),
+            exporter: MetricsExporter::default(),
+        }
+    }
+}
+
+/// 从 `NFA_METRICS_*` 环境变量加载指标配置
+pub fn load_metrics_config_from_env() -> MetricsConfig {
+    let histogram_buckets = std::env::var("NFA_METRICS_HISTOGRAM_BUCKETS")
+        .ok()
+        .and_then(|raw| {
+            raw.split(',')
+                .map(|s| s.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .ok()
+        })
+        .unwrap_or_else(default_histogram_buckets);
+
+    let exporter = match std::env::var("NFA_METRICS_EXPORTER")
+        .unwrap_or_else(|_| "prometheus".to_string())
+        .as_str()
+    {
+        "pushgateway" => MetricsExporter::Pushgateway {
+            url: std::env::var("NFA_METRICS_PUSHGATEWAY_URL")
+                .unwrap_or_else(|_| "http://localhost:9091".to_string()),
+            job: std::env::var("NFA_METRICS_PUSHGATEWAY_JOB")
+                .unwrap_or_else(|_| "nfa_broker".to_string()),
+            interval_secs: std::env::var("NFA_METRICS_PUSHGATEWAY_INTERVAL_SECS")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .unwrap_or(15),
+        },
+        "otlp" => MetricsExporter::Otlp {
+            endpoint: std::env::var("NFA_METRICS_OTLP_ENDPOINT")
+                .unwrap_or_else(|_| "http://localhost:4317".to_string()),
+            interval_secs: std::env::var("NFA_METRICS_OTLP_INTERVAL_SECS")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .unwrap_or(15),
+        },
+        _ => MetricsExporter::Prometheus {
+            path: std::env::var("NFA_METRICS_PROMETHEUS_PATH")
+                .unwrap_or_else(|_| "/metrics".to_string()),
+        },
+    };
+
+    MetricsConfig {
+        histogram_buckets,
+        exporter,
+    }
+}
+
+/// 运行期指标配置，必须在首次访问 `REQUEST_DURATION` 前通过 `configure_metrics` 设置
+static METRICS_CONFIG: once_cell::sync::Lazy<std::sync::RwLock<MetricsConfig>> =
+    once_cell::sync::Lazy::new(|| std::sync::RwLock::new(MetricsConfig::default()));
+
+/// 配置指标子系统（桶边界等）；`init_metrics` 会以其参数调用本函数，
+/// 单独调用时需在首次访问 `REQUEST_DURATION` 之前
+pub fn configure_metrics(config: MetricsConfig) {
+    *METRICS_CONFIG.write().unwrap() = config;
+}
+
 lazy_static! {
-    // 请求计数器
-    pub static ref REQUESTS_TOTAL: Counter = register_counter!(
+    // 按 service/method/status 分组的请求计数器
+    pub static ref REQUESTS_TOTAL: CounterVec = register_counter_vec!(
         "nfa_broker_requests_total",
-        "Total number of requests"
+        "Total number of requests",
+        &["service", "method", "status"]
     ).unwrap();
-    
+
     // 活跃连接数
     pub static ref CONNECTIONS_ACTIVE: Gauge = register_gauge!(
         "nfa_broker_connections_active",
         "Number of active connections"
     ).unwrap();
-    
+
     // 注册服务数
     pub static ref SERVICES_REGISTERED: Gauge = register_gauge!(
         "nfa_broker_services_registered",
         "Number of registered services"
     ).unwrap();
-    
-    // 请求延迟直方图
-    pub static ref REQUEST_DURATION: Histogram = register_histogram!(
-        "nfa_broker_request_duration_seconds",
-        "Request duration in seconds"
+
+    // 按 service/method 分组的请求延迟直方图，桶边界由 MetricsConfig 配置
+    pub static ref REQUEST_DURATION: HistogramVec = register_histogram_vec!(
+        HistogramOpts::new(
+            "nfa_broker_request_duration_seconds",
+            "Request duration in seconds"
+        ).buckets(METRICS_CONFIG.read().unwrap().histogram_buckets.clone()),
+        &["service", "method"]
     ).unwrap();
-    
-    // 错误计数器
-    pub static ref ERRORS_TOTAL: Counter = register_counter!(
+
+    // 按 service/method/error_kind 分组的错误计数器
+    pub static ref ERRORS_TOTAL: CounterVec = register_counter_vec!(
         "nfa_broker_errors_total",
-        "Total number of errors"
+        "Total number of errors",
+        &["service", "method", "error_kind"]
     ).unwrap();
-    
+
+    // 配置热重载失败次数
+    pub static ref CONFIG_RELOAD_FAILURES: prometheus::Counter = prometheus::register_counter!(
+        "nfa_broker_config_reload_failures_total",
+        "Total number of failed configuration hot-reload attempts"
+    ).unwrap();
+
+    // match_intent 请求中未命中任何候选服务的次数
+    pub static ref MATCH_ZERO_CANDIDATES: prometheus::Counter = prometheus::register_counter!(
+        "nfa_broker_match_zero_candidates_total",
+        "Total number of match_intent requests that returned zero candidates"
+    ).unwrap();
+
+    // match_intent 请求中命中至少一个候选服务的次数
+    pub static ref MATCH_INTENT_HITS: prometheus::Counter = prometheus::register_counter!(
+        "nfa_broker_match_intent_hits_total",
+        "Total number of match_intent requests that returned at least one candidate"
+    ).unwrap();
+
+    // 当前健康（未被标记为过期/失联）的已注册服务数
+    pub static ref SERVICES_HEALTHY: Gauge = register_gauge!(
+        "nfa_broker_services_healthy",
+        "Number of registered services currently considered healthy"
+    ).unwrap();
+
     // 内存使用量
     pub static ref MEMORY_USAGE: Gauge = register_gauge!(
         "nfa_broker_memory_usage_bytes",
         "Memory usage in bytes"
     ).unwrap();
+
+    // CPU总体使用率 (0.0 - 1.0)
+    pub static ref CPU_USAGE: Gauge = register_gauge!(
+        "nfa_broker_cpu_usage_ratio",
+        "Overall CPU utilization ratio"
+    ).unwrap();
+
+    // 每核CPU使用率
+    pub static ref CPU_USAGE_PER_CORE: prometheus::GaugeVec = prometheus::register_gauge_vec!(
+        "nfa_broker_cpu_usage_per_core_ratio",
+        "Per-core CPU utilization ratio",
+        &["core"]
+    ).unwrap();
+
+    // 系统负载
+    pub static ref LOAD1: Gauge = register_gauge!("nfa_broker_load1", "1-minute load average").unwrap();
+    pub static ref LOAD5: Gauge = register_gauge!("nfa_broker_load5", "5-minute load average").unwrap();
+    pub static ref LOAD15: Gauge = register_gauge!("nfa_broker_load15", "15-minute load average").unwrap();
+
+    // 系统内存
+    pub static ref SYSTEM_MEMORY_TOTAL: Gauge = register_gauge!(
+        "nfa_broker_system_memory_total_bytes",
+        "Total system memory in bytes"
+    ).unwrap();
+    pub static ref SYSTEM_MEMORY_AVAILABLE: Gauge = register_gauge!(
+        "nfa_broker_system_memory_available_bytes",
+        "Available system memory in bytes"
+    ).unwrap();
+
+    // 进程虚拟内存
+    pub static ref PROCESS_VIRTUAL_MEMORY: Gauge = register_gauge!(
+        "nfa_broker_process_virtual_memory_bytes",
+        "Process virtual memory in bytes"
+    ).unwrap();
+
+    // 打开的文件描述符数
+    pub static ref OPEN_FILE_DESCRIPTORS: Gauge = register_gauge!(
+        "nfa_broker_open_file_descriptors",
+        "Number of open file descriptors"
+    ).unwrap();
+
+    // 存储路径磁盘使用量
+    pub static ref DISK_USED_BYTES: Gauge = register_gauge!(
+        "nfa_broker_disk_used_bytes",
+        "Disk space used at the storage path, in bytes"
+    ).unwrap();
+
+    // 进程运行时长
+    pub static ref PROCESS_UPTIME_SECONDS: Gauge = register_gauge!(
+        "nfa_broker_process_uptime_seconds",
+        "Process uptime in seconds"
+    ).unwrap();
+}
+
+/// 进程启动时间，用于计算运行时长
+static PROCESS_START: once_cell::sync::Lazy<Instant> = once_cell::sync::Lazy::new(Instant::now);
+
+/// 被监控的存储路径（磁盘使用量采集用），默认当前目录
+static STORAGE_PATH: once_cell::sync::Lazy<std::sync::RwLock<std::path::PathBuf>> =
+    once_cell::sync::Lazy::new(|| std::sync::RwLock::new(std::path::PathBuf::from(".")));
+
+/// 设置用于磁盘使用量采集的存储路径
+pub fn set_storage_path<P: Into<std::path::PathBuf>>(path: P) {
+    *STORAGE_PATH.write().unwrap() = path.into();
+}
+
+/// 指标中间件，挂载在配置的路径下（Prometheus exporter 默认 `/metrics`）
+pub fn metrics_middleware(
+    path: &str,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let segment = path.trim_start_matches('/').to_string();
+    warp::path(segment).and_then(serve_metrics)
+}
+
+/// `/sysinfo` JSON 端点，供运维快速查看主机/进程状态（而非 Prometheus 文本格式）
+pub fn sysinfo_middleware() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("sysinfo").and_then(serve_sysinfo)
 }
 
-/// 指标中间件
-pub fn metrics_middleware() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path("metrics").and_then(serve_metrics)
+#[derive(serde::Serialize)]
+struct SysinfoResponse {
+    cpu_usage_ratio: f64,
+    load1: f64,
+    load5: f64,
+    load15: f64,
+    system_memory_total_bytes: f64,
+    system_memory_available_bytes: f64,
+    process_virtual_memory_bytes: f64,
+    process_physical_memory_bytes: f64,
+    open_file_descriptors: f64,
+    disk_used_bytes: f64,
+    process_uptime_seconds: f64,
+}
+
+/// 提供 /sysinfo 端点
+async fn serve_sysinfo() -> Result<impl warp::Reply, warp::Rejection> {
+    let body = SysinfoResponse {
+        cpu_usage_ratio: CPU_USAGE.get(),
+        load1: LOAD1.get(),
+        load5: LOAD5.get(),
+        load15: LOAD15.get(),
+        system_memory_total_bytes: SYSTEM_MEMORY_TOTAL.get(),
+        system_memory_available_bytes: SYSTEM_MEMORY_AVAILABLE.get(),
+        process_virtual_memory_bytes: PROCESS_VIRTUAL_MEMORY.get(),
+        process_physical_memory_bytes: MEMORY_USAGE.get(),
+        open_file_descriptors: OPEN_FILE_DESCRIPTORS.get(),
+        disk_used_bytes: DISK_USED_BYTES.get(),
+        process_uptime_seconds: PROCESS_UPTIME_SECONDS.get(),
+    };
+
+    Ok(warp::reply::json(&body))
 }
 
 /// 提供指标端点
@@ -65,17 +308,19 @@ async fn serve_metrics() -> Result<impl warp::Reply, warp::Rejection> {
     ))
 }
 
-/// 请求计时器
+/// 请求计时器，构造时绑定 service/method 标签，析构时写入对应的直方图分量
 pub struct RequestTimer {
     start: Instant,
-    metric: &'static Histogram,
+    service: String,
+    method: String,
 }
 
 impl RequestTimer {
-    pub fn new(metric: &'static Histogram) -> Self {
+    pub fn new(service: impl Into<String>, method: impl Into<String>) -> Self {
         Self {
             start: Instant::now(),
-            metric,
+            service: service.into(),
+            method: method.into(),
         }
     }
 }
@@ -83,10 +328,22 @@ impl RequestTimer {
 impl Drop for RequestTimer {
     fn drop(&mut self) {
         let duration = self.start.elapsed();
-        self.metric.observe(duration.as_secs_f64());
+        REQUEST_DURATION
+            .with_label_values(&[&self.service, &self.method])
+            .observe(duration.as_secs_f64());
     }
 }
 
+/// 记录一次请求结果：按 service/method/status 递增请求计数（错误计数见 `record_error`）
+pub fn record_request(service: &str, method: &str, status: &str) {
+    REQUESTS_TOTAL.with_label_values(&[service, method, status]).inc();
+}
+
+/// 按 error_kind 维度递增错误计数器
+pub fn record_error(service: &str, method: &str, error_kind: &str) {
+    ERRORS_TOTAL.with_label_values(&[service, method, error_kind]).inc();
+}
+
 /// 更新内存使用指标
 pub async fn update_memory_metrics() {
     task::spawn_blocking(|| {
@@ -96,8 +353,73 @@ pub async fn update_memory_metrics() {
     });
 }
 
-/// 初始化指标
-pub fn init_metrics() {
+/// 采集主机/进程级系统指标（CPU、负载、内存、磁盘、文件描述符、运行时长）
+pub async fn update_system_metrics() {
+    let storage_path = STORAGE_PATH.read().unwrap().clone();
+
+    let _ = task::spawn_blocking(move || {
+        let mut sys = sysinfo::System::new();
+        sys.refresh_cpu();
+        sys.refresh_memory();
+        sys.refresh_processes();
+
+        // CPU 使用率
+        let cpus = sys.cpus();
+        if !cpus.is_empty() {
+            let overall: f32 = cpus.iter().map(|c| c.cpu_usage()).sum::<f32>() / cpus.len() as f32;
+            CPU_USAGE.set((overall / 100.0) as f64);
+
+            for (idx, cpu) in cpus.iter().enumerate() {
+                CPU_USAGE_PER_CORE
+                    .with_label_values(&[&idx.to_string()])
+                    .set((cpu.cpu_usage() / 100.0) as f64);
+            }
+        }
+
+        // 系统负载均值
+        let load = sysinfo::System::load_average();
+        LOAD1.set(load.one);
+        LOAD5.set(load.five);
+        LOAD15.set(load.fifteen);
+
+        // 系统内存
+        SYSTEM_MEMORY_TOTAL.set(sys.total_memory() as f64);
+        SYSTEM_MEMORY_AVAILABLE.set(sys.available_memory() as f64);
+
+        // 当前进程的虚拟内存 / 打开文件描述符 / 运行时长
+        let pid = sysinfo::Pid::from_u32(std::process::id());
+        if let Some(process) = sys.process(pid) {
+            PROCESS_VIRTUAL_MEMORY.set(process.virtual_memory() as f64);
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if let Ok(entries) = std::fs::read_dir("/proc/self/fd") {
+                OPEN_FILE_DESCRIPTORS.set(entries.count() as f64);
+            }
+        }
+
+        PROCESS_UPTIME_SECONDS.set(PROCESS_START.elapsed().as_secs_f64());
+
+        // 存储路径磁盘使用量：匹配挂载路径最长前缀的磁盘
+        let disks = sysinfo::Disks::new_with_refreshed_list();
+        if let Some(disk) = disks
+            .list()
+            .iter()
+            .filter(|d| storage_path.starts_with(d.mount_point()))
+            .max_by_key(|d| d.mount_point().as_os_str().len())
+        {
+            let used = disk.total_space().saturating_sub(disk.available_space());
+            DISK_USED_BYTES.set(used as f64);
+        }
+    })
+    .await;
+}
+
+/// 初始化指标，并根据配置中的 exporter 启动相应的导出任务
+pub fn init_metrics(config: MetricsConfig) {
+    configure_metrics(config.clone());
+
     // 注册所有指标
     lazy_static::initialize(&REQUESTS_TOTAL);
     lazy_static::initialize(&CONNECTIONS_ACTIVE);
@@ -105,13 +427,92 @@ pub fn init_metrics() {
     lazy_static::initialize(&REQUEST_DURATION);
     lazy_static::initialize(&ERRORS_TOTAL);
     lazy_static::initialize(&MEMORY_USAGE);
-    
+    lazy_static::initialize(&CPU_USAGE);
+    lazy_static::initialize(&CPU_USAGE_PER_CORE);
+    lazy_static::initialize(&LOAD1);
+    lazy_static::initialize(&LOAD5);
+    lazy_static::initialize(&LOAD15);
+    lazy_static::initialize(&SYSTEM_MEMORY_TOTAL);
+    lazy_static::initialize(&SYSTEM_MEMORY_AVAILABLE);
+    lazy_static::initialize(&PROCESS_VIRTUAL_MEMORY);
+    lazy_static::initialize(&OPEN_FILE_DESCRIPTORS);
+    lazy_static::initialize(&DISK_USED_BYTES);
+    lazy_static::initialize(&PROCESS_UPTIME_SECONDS);
+    lazy_static::initialize(&CONFIG_RELOAD_FAILURES);
+    lazy_static::initialize(&MATCH_ZERO_CANDIDATES);
+    lazy_static::initialize(&MATCH_INTENT_HITS);
+    lazy_static::initialize(&SERVICES_HEALTHY);
+
     // 启动定期指标更新
     tokio::spawn(async {
         let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
         loop {
             interval.tick().await;
             update_memory_metrics().await;
+            update_system_metrics().await;
         }
     });
+
+    // 启动指标导出任务：拉取模式无需额外任务，推送模式周期性推送
+    spawn_exporter(config.exporter);
+}
+
+/// 根据配置的 exporter 变体启动对应的后台导出任务
+fn spawn_exporter(exporter: MetricsExporter) {
+    match exporter {
+        MetricsExporter::Prometheus { .. } => {
+            // 由 metrics_middleware 挂载的 /metrics 端点被动拉取，无需后台任务
+        }
+        MetricsExporter::Pushgateway { url, job, interval_secs } => {
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = push_to_pushgateway(&url, &job).await {
+                        tracing::warn!("Failed to push metrics to Pushgateway: {}", e);
+                    }
+                }
+            });
+        }
+        MetricsExporter::Otlp { endpoint, interval_secs } => {
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = push_to_otlp(&endpoint).await {
+                        tracing::warn!("Failed to export metrics over OTLP: {}", e);
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// 将当前已采集的指标族推送到 Pushgateway
+async fn push_to_pushgateway(url: &str, job: &str) -> Result<(), String> {
+    let metric_families = prometheus::gather();
+    let url = url.to_string();
+    let job = job.to_string();
+
+    task::spawn_blocking(move || {
+        prometheus::push_metrics(
+            &job,
+            prometheus::labels! {},
+            &url,
+            metric_families,
+            None,
+        )
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 通过 OTLP 导出已采集的指标族。尚未实现——真正的导出需要引入 opentelemetry-otlp，
+/// 将 Prometheus 指标族转换为 OTLP 指标并通过 gRPC/HTTP 推送到 collector；在此之前
+/// 返回错误而非悄悄丢弃指标，使 `spawn_exporter` 的告警能如实反映该导出方式当前不可用
+async fn push_to_otlp(endpoint: &str) -> Result<(), String> {
+    Err(format!(
+        "OTLP export to {endpoint} is not implemented yet; use the prometheus or pushgateway exporter instead"
+    ))
 }
\ No newline at end of file
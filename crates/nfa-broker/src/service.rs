@@ -1,27 +1,81 @@
+use crate::auth::{auth_context, IdentityVerifier};
+use crate::health::HealthState;
 use crate::BrokerError;
+use futures::StreamExt;
 use nfa_common::intent::{IntentRequest, IntentResponse};
 use nfa_idl::IntentContract;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tonic::{Request, Response, Status};
 
+/// 事件环形缓冲区保留的最大条目数；超出后丢弃最旧的事件。`nfa watch --since` 早于
+/// 缓冲区最旧游标的请求将无法完整重放，需要重新拉取一次全量快照
+const EVENT_LOG_CAPACITY: usize = 256;
+
 use nfa::intent::v1alpha::{
     intent_broker_server::IntentBroker, RegisterIntentRequest, RegisterIntentResponse,
     IntentMatchRequest, IntentMatchResponse, IntentContract as ProtoIntentContract,
+    HeartbeatRequest, HeartbeatResponse, UnregisterIntentRequest, UnregisterIntentResponse,
 };
 
 #[derive(Debug, Default)]
 pub struct RegisteredService {
     pub contract: IntentContract,
     pub last_heartbeat: std::time::Instant,
-    pub is_healthy: bool,
+    pub health_state: HealthState,
+    /// 连续错过心跳周期的次数；每次按时收到心跳即重置为 0
+    pub consecutive_missed_beats: u32,
+    /// 最近一次心跳与前一次心跳的实际间隔（毫秒），作为心跳节律健康度的简化代理指标
+    pub last_reported_latency: Option<u64>,
+    /// register_intent 时校验出的调用方身份，未启用鉴权时恒为 "anonymous"
+    pub owner: String,
 }
 
-#[derive(Debug, Default)]
+impl RegisteredService {
+    /// 是否仍可作为 `match_intent`/`watch_intent` 的候选：Healthy/Suspect 均可用，
+    /// 只有连续错过心跳达到阈值判定为 Unhealthy 后才从候选集中排除
+    fn is_available(&self) -> bool {
+        !matches!(self.health_state, HealthState::Unhealthy)
+    }
+}
+
+/// 服务注册状态变更的种类
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceEventKind {
+    Registered,
+    Unregistered,
+}
+
+/// 一次已记录的服务变更事件，按 action 分组广播给 `watch_intent` 订阅者，
+/// 并以 `cursor` 为键追加到环形缓冲区；`service_ids` 是发布时重新计算出的匹配快照，
+/// 而非仅仅是触发信号，使订阅者与 `--since` 重放客户端都无需再次往返查询
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WatchEvent {
+    pub cursor: u64,
+    pub action: String,
+    pub kind: ServiceEventKind,
+    pub service_ids: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone)]
 pub struct BrokerService {
     services: Arc<RwLock<HashMap<String, RegisteredService>>>,
     pattern_index: Arc<RwLock<HashMap<String, Vec<String>>>>, // pattern -> service_ids
+    event_channels: Arc<RwLock<HashMap<String, broadcast::Sender<WatchEvent>>>>, // action -> 变更事件广播通道
+    /// 最近发布的变更事件环形缓冲区，供 `nfa watch --since <cursor>` 等订阅者追平错过的历史
+    event_log: Arc<RwLock<VecDeque<WatchEvent>>>,
+    next_cursor: Arc<AtomicU64>,
+    /// 未配置时鉴权保持关闭（向后兼容），任何调用方均可操作任意 service_id
+    verifier: Option<Arc<dyn IdentityVerifier>>,
+    /// 未配置时注册表纯存于内存（向后兼容），配置后 register/unregister 会写穿到该存储，
+    /// 并支持 broker 重启后通过 `rehydrate` 从中恢复
+    storage: Option<Arc<dyn crate::storage::StorageBackend>>,
+    /// 心跳 TTL，用于在写穿 `storage` 时据此设置存活键的有效期（如 Redis 的 `SETEX`）；
+    /// 未配置 `storage` 时不生效
+    heartbeat_ttl: std::time::Duration,
 }
 
 #[tonic::async_trait]
@@ -30,34 +84,75 @@ impl IntentBroker for BrokerService {
         &self,
         request: Request<RegisterIntentRequest>,
     ) -> Result<Response<RegisterIntentResponse>, Status> {
+        // 签名校验（如 SignedKeypairVerifier）需要对契约的规范化字节签名，而该字节
+        // 只有解码请求体之后才能得到，因此先捕获元数据/证书，待契约就绪后再补上签名负载。
+        // 签名覆盖的是客户端发送的 proto 编码字节本身，而非 proto_to_contract 转换后的内部
+        // 契约类型——proto⇄内部契约的转换目前是有损的简化实现（如下方会清空 intent_patterns），
+        // 双方唯一能确定性复现的只有这份原始 proto 字节
+        let context = auth_context(&request);
         let req = request.into_inner();
         let proto_contract = req.contract.ok_or(Status::invalid_argument("contract is required"))?;
-        
+        let signed_payload = prost::Message::encode_to_vec(&proto_contract);
+
         // Convert proto contract to internal representation
         let contract = self.proto_to_contract(proto_contract)?;
-        
+
         // Validate contract
         nfa_idl::validate_contract(&contract)
             .map_err(|e| Status::invalid_argument(e.to_string()))?;
-        
+
+        let owner = self.authenticate(&context.with_payload(signed_payload))?;
+
         // Generate service ID
         let service_id = format!("{}-{}", contract.metadata.name, uuid::Uuid::new_v4());
-        
-        // Register service
+
+        // 若配置了持久化存储，先行写穿；写入失败则直接拒绝，避免内存态与存储态分叉。
+        // 同时写入一次存活标记，避免注册成功后、首次心跳之前的窗口被后台 reaper 误判为过期
+        if let Some(storage) = &self.storage {
+            storage
+                .store_service(service_id.clone(), contract.clone())
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?;
+            storage
+                .touch_service(&service_id, std::time::Instant::now(), self.heartbeat_ttl)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?;
+        }
+
+        // Register service, binding it to the authenticated caller's identity
         let mut services = self.services.write().await;
         services.insert(
             service_id.clone(),
             RegisteredService {
                 contract,
                 last_heartbeat: std::time::Instant::now(),
-                is_healthy: true,
+                health_state: HealthState::Healthy,
+                consecutive_missed_beats: 0,
+                last_reported_latency: None,
+                owner,
             },
         );
         
         // Index patterns
         self.index_patterns(&service_id, &services[&service_id].contract)
             .await;
-        
+
+        let actions: Vec<String> = services[&service_id]
+            .contract
+            .spec
+            .intent_patterns
+            .iter()
+            .map(|pattern| pattern.pattern.action.clone())
+            .collect();
+        crate::metrics::SERVICES_REGISTERED.set(services.len() as f64);
+        drop(services);
+
+        for action in &actions {
+            self.publish_event(action, ServiceEventKind::Registered).await;
+        }
+
+        crate::metrics::record_request("broker", "register_intent", "ok");
+
         Ok(Response::new(RegisterIntentResponse {
             service_id,
             success: true,
@@ -69,36 +164,420 @@ impl IntentBroker for BrokerService {
         &self,
         request: Request<IntentMatchRequest>,
     ) -> Result<Response<IntentMatchResponse>, Status> {
+        let _timer = crate::metrics::RequestTimer::new("broker", "match_intent");
         let req = request.into_inner();
         let action = req.action.ok_or(Status::invalid_argument("action is required"))?;
-        
-        // Find matching services
+
+        // 参数以字符串形式到达，按最佳努力转换为 JSON 值供约束校验使用
+        let parameters = req
+            .parameters
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+            .collect();
+        let intent_request = IntentRequest {
+            action: action.clone(),
+            parameters,
+            context: None,
+        };
+
+        // 先用模式索引按 action 精确匹配，缩小候选范围
         let pattern_index = self.pattern_index.read().await;
         let services = self.services.read().await;
-        
-        let mut matches = Vec::new();
-        
-        if let Some(service_ids) = pattern_index.get(&action) {
-            for service_id in service_ids {
-                if let Some(service) = services.get(service_id) {
-                    if service.is_healthy {
-                        matches.push(service_id.clone());
-                    }
+
+        let candidates: Vec<(&String, &IntentContract)> = pattern_index
+            .get(&action)
+            .into_iter()
+            .flatten()
+            .filter_map(|service_id| {
+                services.get(service_id).and_then(|service| {
+                    service
+                        .is_available()
+                        .then_some((service_id, &service.contract))
+                })
+            })
+            .collect();
+
+        let ranked = crate::matching::match_candidates(&intent_request, candidates);
+
+        crate::metrics::record_request("broker", "match_intent", "ok");
+        if ranked.is_empty() {
+            crate::metrics::MATCH_ZERO_CANDIDATES.inc();
+        } else {
+            crate::metrics::MATCH_INTENT_HITS.inc();
+        }
+
+        Ok(Response::new(IntentMatchResponse {
+            service_ids: ranked.into_iter().map(|c| c.service_id).collect(),
+        }))
+    }
+
+    async fn heartbeat(
+        &self,
+        request: Request<HeartbeatRequest>,
+    ) -> Result<Response<HeartbeatResponse>, Status> {
+        let owner = self.authenticate(&auth_context(&request))?;
+        let req = request.into_inner();
+
+        {
+            let mut services = self.services.write().await;
+            match services.get_mut(&req.service_id) {
+                Some(service) if service.owner == owner => {
+                    service.last_reported_latency =
+                        Some(service.last_heartbeat.elapsed().as_millis() as u64);
+                    service.last_heartbeat = std::time::Instant::now();
+                    service.health_state = HealthState::Healthy;
+                    service.consecutive_missed_beats = 0;
+                }
+                Some(_) => {
+                    return Err(Status::permission_denied(format!(
+                        "caller does not own service_id: {}",
+                        req.service_id
+                    )))
+                }
+                None => {
+                    return Err(Status::not_found(format!(
+                        "unknown service_id: {}",
+                        req.service_id
+                    )))
                 }
             }
         }
-        
-        Ok(Response::new(IntentMatchResponse {
-            service_ids: matches,
+
+        // 写穿存活标记，使配置了 storage 的后端（尤其是依赖原生过期机制的 Redis）据此续约，
+        // 否则后台 reaper 会在下一轮按 storage 自身的存活记录而非本进程内存判定其失联
+        if let Some(storage) = &self.storage {
+            storage
+                .touch_service(&req.service_id, std::time::Instant::now(), self.heartbeat_ttl)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?;
+        }
+
+        Ok(Response::new(HeartbeatResponse { success: true }))
+    }
+
+    async fn unregister_intent(
+        &self,
+        request: Request<UnregisterIntentRequest>,
+    ) -> Result<Response<UnregisterIntentResponse>, Status> {
+        let owner = self.authenticate(&auth_context(&request))?;
+        let req = request.into_inner();
+
+        let mut services = self.services.write().await;
+        let actions = match services.get(&req.service_id) {
+            Some(service) if service.owner == owner => service
+                .contract
+                .spec
+                .intent_patterns
+                .iter()
+                .map(|pattern| pattern.pattern.action.clone())
+                .collect::<Vec<_>>(),
+            Some(_) => {
+                return Err(Status::permission_denied(format!(
+                    "caller does not own service_id: {}",
+                    req.service_id
+                )))
+            }
+            None => {
+                return Err(Status::not_found(format!(
+                    "unknown service_id: {}",
+                    req.service_id
+                )))
+            }
+        };
+
+        services.remove(&req.service_id);
+        crate::metrics::SERVICES_REGISTERED.set(services.len() as f64);
+        drop(services);
+
+        if let Some(storage) = &self.storage {
+            storage
+                .delete_service(&req.service_id)
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?;
+        }
+
+        let mut pattern_index = self.pattern_index.write().await;
+        for service_ids in pattern_index.values_mut() {
+            service_ids.retain(|id| id != &req.service_id);
+        }
+        pattern_index.retain(|_, service_ids| !service_ids.is_empty());
+        drop(pattern_index);
+
+        for action in &actions {
+            self.publish_event(action, ServiceEventKind::Unregistered).await;
+        }
+
+        crate::metrics::record_request("broker", "unregister_intent", "ok");
+
+        Ok(Response::new(UnregisterIntentResponse {
+            success: true,
+            message: "Service unregistered successfully".to_string(),
         }))
     }
+
+    type WatchIntentStream =
+        std::pin::Pin<Box<dyn futures::Stream<Item = Result<IntentMatchResponse, Status>> + Send>>;
+
+    /// 保持订阅打开：每当该 action 下有服务注册或被清理，就重新计算匹配快照并推送，
+    /// 免去长期运行的 agent 反复轮询 `match_intent`
+    async fn watch_intent(
+        &self,
+        request: Request<IntentMatchRequest>,
+    ) -> Result<Response<Self::WatchIntentStream>, Status> {
+        let req = request.into_inner();
+        let action = req.action.ok_or(Status::invalid_argument("action is required"))?;
+
+        let receiver = self.subscribe(&action).await;
+
+        // 事件中已携带发布时计算出的匹配快照，订阅者直接转发即可，免去再次查询模式索引；
+        // 落后被丢弃消息（Lagged）时跳过一次，不终止订阅
+        let stream = tokio_stream::wrappers::BroadcastStream::new(receiver).filter_map(|event| async move {
+            let event = event.ok()?;
+            Some(Ok(IntentMatchResponse {
+                service_ids: event.service_ids,
+            }))
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// 服务概要信息，供管理端点展示
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ServiceSummary {
+    pub service_id: String,
+    pub name: String,
+    pub health_state: HealthState,
+    pub consecutive_missed_beats: u32,
+    pub last_heartbeat_secs_ago: u64,
 }
 
 impl BrokerService {
     pub fn new() -> Self {
         Self::default()
     }
-    
+
+    /// 当前已注册服务数，供指标子系统更新 `SERVICES_REGISTERED` 仪表
+    pub async fn service_count(&self) -> usize {
+        self.services.read().await.len()
+    }
+
+    /// 配置身份校验器；须在服务被 `clone` 分发给后台任务（如 reaper）之前调用
+    pub(crate) fn set_verifier(&mut self, verifier: Arc<dyn IdentityVerifier>) {
+        self.verifier = Some(verifier);
+    }
+
+    /// 配置持久化存储后端；须在服务被 `clone` 分发给后台任务之前调用
+    pub(crate) fn set_storage(&mut self, storage: Arc<dyn crate::storage::StorageBackend>) {
+        self.storage = Some(storage);
+    }
+
+    /// 配置心跳 TTL，供写穿 `storage` 时设置存活键有效期；须在服务被 `clone` 分发给后台任务之前调用
+    pub(crate) fn set_heartbeat_ttl(&mut self, ttl: std::time::Duration) {
+        self.heartbeat_ttl = ttl;
+    }
+
+    /// 从持久化存储恢复服务注册表，用于 broker 重启后的状态重建；未配置 `storage` 时为 no-op。
+    /// 存储层不保留调用方归属信息，恢复的服务统一记为 "anonymous"；配置了签名校验时，
+    /// 已签名调用方对这些服务的 `heartbeat`/`unregister_intent` 会因归属不符被拒绝，归属不会被重新绑定
+    pub async fn rehydrate(&self) -> Result<(), crate::storage::StorageError> {
+        let Some(storage) = self.storage.clone() else {
+            return Ok(());
+        };
+
+        for service_id in storage.get_all_service_ids().await? {
+            let Some(contract) = storage.get_service(&service_id).await? else {
+                continue;
+            };
+
+            {
+                let mut services = self.services.write().await;
+                services.insert(
+                    service_id.clone(),
+                    RegisteredService {
+                        contract: contract.clone(),
+                        last_heartbeat: std::time::Instant::now(),
+                        health_state: HealthState::Healthy,
+                        consecutive_missed_beats: 0,
+                        last_reported_latency: None,
+                        owner: "anonymous".to_string(),
+                    },
+                );
+            }
+            self.index_patterns(&service_id, &contract).await;
+        }
+
+        crate::metrics::SERVICES_REGISTERED.set(self.services.read().await.len() as f64);
+        Ok(())
+    }
+
+    /// 校验调用方身份：未配置校验器时鉴权关闭，统一视为 "anonymous"
+    fn authenticate(&self, context: &crate::auth::AuthContext) -> Result<String, Status> {
+        match &self.verifier {
+            Some(verifier) => verifier
+                .verify(context)
+                .map(|identity| identity.0)
+                .map_err(|e| Status::unauthenticated(e.to_string())),
+            None => Ok("anonymous".to_string()),
+        }
+    }
+
+    /// 注册意图服务，供 WebSocket 等非 gRPC 传输复用；直接接受内部契约类型，跳过 proto 转换，
+    /// 调用方身份固定为 "anonymous"（WS 传输尚未接入 [`IdentityVerifier`] 鉴权）
+    pub async fn register_intent_raw(&self, contract: IntentContract) -> Result<String, BrokerError> {
+        let service_id = format!("{}-{}", contract.metadata.name, uuid::Uuid::new_v4());
+
+        if let Some(storage) = &self.storage {
+            storage
+                .store_service(service_id.clone(), contract.clone())
+                .await
+                .map_err(|e| BrokerError::StorageError(e.to_string()))?;
+            storage
+                .touch_service(&service_id, std::time::Instant::now(), self.heartbeat_ttl)
+                .await
+                .map_err(|e| BrokerError::StorageError(e.to_string()))?;
+        }
+
+        let mut services = self.services.write().await;
+        services.insert(
+            service_id.clone(),
+            RegisteredService {
+                contract,
+                last_heartbeat: std::time::Instant::now(),
+                health_state: HealthState::Healthy,
+                consecutive_missed_beats: 0,
+                last_reported_latency: None,
+                owner: "anonymous".to_string(),
+            },
+        );
+
+        self.index_patterns(&service_id, &services[&service_id].contract)
+            .await;
+
+        let actions: Vec<String> = services[&service_id]
+            .contract
+            .spec
+            .intent_patterns
+            .iter()
+            .map(|pattern| pattern.pattern.action.clone())
+            .collect();
+        crate::metrics::SERVICES_REGISTERED.set(services.len() as f64);
+        drop(services);
+
+        for action in &actions {
+            self.publish_event(action, ServiceEventKind::Registered).await;
+        }
+
+        Ok(service_id)
+    }
+
+    /// 按 action 匹配候选服务，供 WebSocket 等非 gRPC 传输复用；逻辑与 gRPC `match_intent` 一致
+    pub async fn match_intent_raw(
+        &self,
+        action: &str,
+        parameters: HashMap<String, serde_json::Value>,
+    ) -> Vec<String> {
+        let intent_request = IntentRequest {
+            action: action.to_string(),
+            parameters,
+            context: None,
+        };
+
+        let pattern_index = self.pattern_index.read().await;
+        let services = self.services.read().await;
+
+        let candidates: Vec<(&String, &IntentContract)> = pattern_index
+            .get(action)
+            .into_iter()
+            .flatten()
+            .filter_map(|service_id| {
+                services.get(service_id).and_then(|service| {
+                    service
+                        .is_available()
+                        .then_some((service_id, &service.contract))
+                })
+            })
+            .collect();
+
+        crate::matching::match_candidates(&intent_request, candidates)
+            .into_iter()
+            .map(|c| c.service_id)
+            .collect()
+    }
+
+    /// 列出所有已注册服务及其健康状态，供管理端点使用
+    pub async fn list_services(&self) -> Vec<ServiceSummary> {
+        let services = self.services.read().await;
+        services
+            .iter()
+            .map(|(service_id, registered)| ServiceSummary {
+                service_id: service_id.clone(),
+                name: registered.contract.metadata.name.clone(),
+                health_state: registered.health_state,
+                consecutive_missed_beats: registered.consecutive_missed_beats,
+                last_heartbeat_secs_ago: registered.last_heartbeat.elapsed().as_secs(),
+            })
+            .collect()
+    }
+
+    /// 订阅某个 action 下的注册/注销事件；该 action 尚无订阅者时自动创建广播通道
+    async fn subscribe(&self, action: &str) -> broadcast::Receiver<WatchEvent> {
+        let mut channels = self.event_channels.write().await;
+        channels
+            .entry(action.to_string())
+            .or_insert_with(|| broadcast::channel(32).0)
+            .subscribe()
+    }
+
+    /// 订阅某个 action 的变更事件，并一并返回游标晚于 `since` 的历史事件（若提供），
+    /// 供 `nfa watch --since <cursor>` 等客户端在重新连接后先追平错过的事件，再切换到实时推送；
+    /// 缓冲区已经滚动丢弃的历史游标将被静默跳过，不视为错误
+    pub async fn watch_events(
+        &self,
+        action: &str,
+        since: Option<u64>,
+    ) -> (Vec<WatchEvent>, broadcast::Receiver<WatchEvent>) {
+        let receiver = self.subscribe(action).await;
+        let backlog = match since {
+            Some(cursor) => self
+                .event_log
+                .read()
+                .await
+                .iter()
+                .filter(|event| event.action == action && event.cursor > cursor)
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        (backlog, receiver)
+    }
+
+    /// 记录并广播一次变更事件：分配单调递增的游标，重新计算该 action 当前的匹配快照，
+    /// 追加到环形缓冲区（超出容量时丢弃最旧条目），再广播给实时订阅者；没有订阅者时静默忽略
+    async fn publish_event(&self, action: &str, kind: ServiceEventKind) {
+        let cursor = self.next_cursor.fetch_add(1, Ordering::SeqCst);
+        let service_ids = self.match_intent_raw(action, HashMap::new()).await;
+        let event = WatchEvent {
+            cursor,
+            action: action.to_string(),
+            kind,
+            service_ids,
+        };
+
+        {
+            let mut log = self.event_log.write().await;
+            if log.len() >= EVENT_LOG_CAPACITY {
+                log.pop_front();
+            }
+            log.push_back(event.clone());
+        }
+
+        let channels = self.event_channels.read().await;
+        if let Some(sender) = channels.get(action) {
+            let _ = sender.send(event);
+        }
+    }
+
     async fn index_patterns(&self, service_id: &str, contract: &IntentContract) {
         let mut pattern_index = self.pattern_index.write().await;
         
@@ -138,12 +617,96 @@ impl BrokerService {
         })
     }
     
-    pub async fn health_check(&self) {
-        // Periodically check service health
+    /// 按心跳状态机推进每个已注册服务的健康状态：超过 `tolerance` 未续约心跳即错过一个周期，
+    /// 第一次错过进入 Suspect，连续错过达到 `unhealthy_after` 次后判定为 Unhealthy；
+    /// 期间收到心跳的服务已在 `heartbeat` 处理中直接重置，这里只处理静默失联的服务
+    pub async fn update_health_states(&self, tolerance: std::time::Duration, unhealthy_after: u32) {
+        let mut services = self.services.write().await;
+        for service in services.values_mut() {
+            if service.last_heartbeat.elapsed() > tolerance {
+                service.consecutive_missed_beats += 1;
+                service.health_state = if service.consecutive_missed_beats >= unhealthy_after {
+                    HealthState::Unhealthy
+                } else {
+                    HealthState::Suspect
+                };
+            } else {
+                service.consecutive_missed_beats = 0;
+                service.health_state = HealthState::Healthy;
+            }
+        }
+        let healthy = services
+            .values()
+            .filter(|service| service.health_state == HealthState::Healthy)
+            .count();
+        crate::metrics::SERVICES_HEALTHY.set(healthy as f64);
+    }
+
+    /// 清理已失联的服务，并将其从模式索引中移除；返回被清理的 service_id 列表。配置了 `storage`
+    /// 时，以存储层自身的存活追踪（经 `touch_service` 在注册/心跳时写入）作为清理依据，并写穿
+    /// `StorageBackend::reap_expired`，确保被清理的服务不会在重启后经 `rehydrate` 复活；
+    /// 未配置 `storage`（或其调用失败）时退回基于内存 `last_heartbeat` 的判断
+    pub async fn reap_expired(&self, ttl: std::time::Duration) -> Vec<String> {
+        let expired_ids = match &self.storage {
+            Some(storage) => match storage.reap_expired(ttl).await {
+                Ok(ids) => ids,
+                Err(e) => {
+                    tracing::warn!(
+                        "storage reap_expired failed, falling back to in-memory heartbeat tracking: {}",
+                        e
+                    );
+                    self.expired_ids_by_heartbeat(ttl).await
+                }
+            },
+            None => self.expired_ids_by_heartbeat(ttl).await,
+        };
+
+        if expired_ids.is_empty() {
+            return expired_ids;
+        }
+
         let mut services = self.services.write().await;
-        for (_, service) in services.iter_mut() {
-            let elapsed = service.last_heartbeat.elapsed();
-            service.is_healthy = elapsed.as_secs() < 30; // 30 second timeout
+        // 保留每个过期服务声明的 action，以便移除后仍能通知对应的 watch_intent 订阅者
+        let actions: Vec<String> = expired_ids
+            .iter()
+            .filter_map(|service_id| services.get(service_id))
+            .flat_map(|service| {
+                service
+                    .contract
+                    .spec
+                    .intent_patterns
+                    .iter()
+                    .map(|pattern| pattern.pattern.action.clone())
+            })
+            .collect();
+
+        for service_id in &expired_ids {
+            services.remove(service_id);
+        }
+        drop(services);
+
+        let mut pattern_index = self.pattern_index.write().await;
+        for service_ids in pattern_index.values_mut() {
+            service_ids.retain(|id| !expired_ids.contains(id));
         }
+        pattern_index.retain(|_, service_ids| !service_ids.is_empty());
+        drop(pattern_index);
+
+        for action in &actions {
+            self.publish_event(action, ServiceEventKind::Unregistered).await;
+        }
+
+        expired_ids
+    }
+
+    /// 基于内存中 `last_heartbeat` 判断过期服务，供未配置 `storage`（或其 `reap_expired` 失败）时使用
+    async fn expired_ids_by_heartbeat(&self, ttl: std::time::Duration) -> Vec<String> {
+        self.services
+            .read()
+            .await
+            .iter()
+            .filter(|(_, service)| service.last_heartbeat.elapsed() > ttl)
+            .map(|(service_id, _)| service_id.clone())
+            .collect()
     }
 }
\ No newline at end of file
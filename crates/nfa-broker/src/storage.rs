@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use nfa_idl::IntentContract;
+use sled::transaction::Transactional;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -9,14 +10,69 @@ use thiserror::Error;
 pub enum StorageError {
     #[error("Service not found: {0}")]
     ServiceNotFound(String),
-    
+
     #[error("Service already exists: {0}")]
     ServiceAlreadyExists(String),
-    
+
     #[error("Database error: {0}")]
     Database(String),
+
+    #[error("Connection pool error: {0}")]
+    Pool(String),
+
+    #[error("Migration error: {0}")]
+    Migration(String),
 }
 
+/// 根据配置构造对应的存储后端实例，供 broker 启动时按 `StorageBackendConfig` 装配
+/// 持久化/可恢复的服务注册表
+pub async fn build_storage(
+    config: &crate::config::StorageBackendConfig,
+) -> Result<Arc<dyn StorageBackend>, StorageError> {
+    use crate::config::StorageBackendConfig;
+
+    Ok(match config {
+        StorageBackendConfig::Memory => Arc::new(MemoryStorage::new()),
+        StorageBackendConfig::Redis { url, prefix } => Arc::new(RedisStorage::new(url, prefix)?),
+        StorageBackendConfig::Postgres {
+            url,
+            table_prefix,
+            pool_max_size,
+            pool_timeout_secs,
+        } => Arc::new(PostgresStorage::new(url, table_prefix, *pool_max_size, *pool_timeout_secs).await?),
+        StorageBackendConfig::Sqlite { path } => Arc::new(SqliteStorage::new(path)?),
+        StorageBackendConfig::Lmdb { path, map_size_mb } => Arc::new(LmdbStorage::new(path, *map_size_mb)?),
+        StorageBackendConfig::Sled { path } => Arc::new(SledStorage::new(path)?),
+    })
+}
+
+/// 一条按顺序应用的迁移：版本号 + 建表/建索引 SQL
+type Migration = (u32, &'static str);
+
+/// Postgres 表结构迁移脚本，按版本号顺序应用
+const POSTGRES_MIGRATIONS: &[Migration] = &[
+    (
+        1,
+        "CREATE TABLE IF NOT EXISTS {prefix}services (\
+            service_id TEXT PRIMARY KEY, \
+            contract JSONB NOT NULL\
+        )",
+    ),
+    (
+        2,
+        "CREATE TABLE IF NOT EXISTS {prefix}pattern_index (\
+            pattern TEXT NOT NULL, \
+            service_id TEXT NOT NULL, \
+            PRIMARY KEY (pattern, service_id)\
+        )",
+    ),
+    (
+        3,
+        "CREATE INDEX IF NOT EXISTS {prefix}pattern_index_pattern_idx \
+            ON {prefix}pattern_index (pattern)",
+    ),
+];
+
 /// 存储后端 trait
 #[async_trait]
 pub trait StorageBackend: Send + Sync {
@@ -34,12 +90,24 @@ pub trait StorageBackend: Send + Sync {
     
     /// 按意图模式查找服务
     async fn find_services_by_pattern(&self, pattern: &str) -> Result<Vec<String>, StorageError>;
+
+    /// 记录一次心跳；`ttl` 供支持原生过期的后端（如 Redis）据此设置存活键的有效期
+    async fn touch_service(
+        &self,
+        service_id: &str,
+        now: std::time::Instant,
+        ttl: std::time::Duration,
+    ) -> Result<(), StorageError>;
+
+    /// 清理最近一次心跳早于 `ttl` 的服务，返回被清理的 service_id 列表
+    async fn reap_expired(&self, ttl: std::time::Duration) -> Result<Vec<String>, StorageError>;
 }
 
 /// 内存存储后端（用于开发和测试）
 pub struct MemoryStorage {
     services: Arc<RwLock<HashMap<String, IntentContract>>>,
     pattern_index: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    liveness: Arc<RwLock<HashMap<String, std::time::Instant>>>,
 }
 
 impl MemoryStorage {
@@ -47,6 +115,7 @@ impl MemoryStorage {
         Self {
             services: Arc::new(RwLock::new(HashMap::new())),
             pattern_index: Arc::new(RwLock::new(HashMap::new())),
+            liveness: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
@@ -71,15 +140,17 @@ impl StorageBackend for MemoryStorage {
                 .or_insert_with(Vec::new)
                 .push(service_id.clone());
         }
-        
+
+        self.liveness.write().await.insert(service_id, std::time::Instant::now());
+
         Ok(())
     }
-    
+
     async fn get_service(&self, service_id: &str) -> Result<Option<IntentContract>, StorageError> {
         let services = self.services.read().await;
         Ok(services.get(service_id).cloned())
     }
-    
+
     async fn delete_service(&self, service_id: &str) -> Result<(), StorageError> {
         let mut services = self.services.write().await;
         
@@ -103,19 +174,574 @@ impl StorageBackend for MemoryStorage {
         
         // 从服务存储中移除
         services.remove(service_id);
-        
+        self.liveness.write().await.remove(service_id);
+
         Ok(())
     }
-    
+
     async fn get_all_service_ids(&self) -> Result<Vec<String>, StorageError> {
         let services = self.services.read().await;
         Ok(services.keys().cloned().collect())
     }
-    
+
     async fn find_services_by_pattern(&self, pattern: &str) -> Result<Vec<String>, StorageError> {
         let pattern_index = self.pattern_index.read().await;
         Ok(pattern_index.get(pattern).cloned().unwrap_or_default())
     }
+
+    async fn touch_service(
+        &self,
+        service_id: &str,
+        now: std::time::Instant,
+        _ttl: std::time::Duration,
+    ) -> Result<(), StorageError> {
+        self.liveness.write().await.insert(service_id.to_string(), now);
+        Ok(())
+    }
+
+    async fn reap_expired(&self, ttl: std::time::Duration) -> Result<Vec<String>, StorageError> {
+        let expired: Vec<String> = self
+            .liveness
+            .read()
+            .await
+            .iter()
+            .filter(|(_, last_seen)| last_seen.elapsed() > ttl)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for service_id in &expired {
+            self.delete_service(service_id).await?;
+        }
+
+        Ok(expired)
+    }
+}
+
+/// Postgres存储后端（生产环境，带连接池与自动迁移）
+pub struct PostgresStorage {
+    pool: deadpool_postgres::Pool,
+    table_prefix: String,
+    // 心跳存活状态是进程内瞬时信号，重启后由下一次心跳重新建立，无需落库
+    liveness: Arc<RwLock<HashMap<String, std::time::Instant>>>,
+}
+
+impl PostgresStorage {
+    pub async fn new(
+        url: &str,
+        table_prefix: &str,
+        pool_max_size: u32,
+        pool_timeout_secs: u64,
+    ) -> Result<Self, StorageError> {
+        let mut pg_config = url
+            .parse::<tokio_postgres::Config>()
+            .map_err(|e| StorageError::Pool(e.to_string()))?;
+        pg_config.application_name("nfa-broker");
+
+        let mgr = deadpool_postgres::Manager::new(pg_config, tokio_postgres::NoTls);
+        let pool = deadpool_postgres::Pool::builder(mgr)
+            .max_size(pool_max_size as usize)
+            .wait_timeout(Some(std::time::Duration::from_secs(pool_timeout_secs)))
+            .build()
+            .map_err(|e| StorageError::Pool(e.to_string()))?;
+
+        let storage = Self {
+            pool,
+            table_prefix: table_prefix.to_string(),
+            liveness: Arc::new(RwLock::new(HashMap::new())),
+        };
+        storage.run_migrations().await?;
+
+        Ok(storage)
+    }
+
+    /// 在事务加咨询锁内按版本号顺序应用迁移，并记录已应用版本，使重复运行幂等
+    async fn run_migrations(&self) -> Result<(), StorageError> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| StorageError::Pool(e.to_string()))?;
+        let txn = conn
+            .transaction()
+            .await
+            .map_err(|e| StorageError::Migration(e.to_string()))?;
+
+        // 咨询锁防止多个 broker 实例并发迁移同一张表
+        txn.execute("SELECT pg_advisory_xact_lock($1)", &[&0x6e66615f6462i64])
+            .await
+            .map_err(|e| StorageError::Migration(e.to_string()))?;
+
+        let migrations_table = format!("{}schema_migrations", self.table_prefix);
+        txn.execute(
+            &format!(
+                "CREATE TABLE IF NOT EXISTS {} (version INTEGER PRIMARY KEY, applied_at TIMESTAMPTZ NOT NULL DEFAULT now())",
+                migrations_table
+            ),
+            &[],
+        )
+        .await
+        .map_err(|e| StorageError::Migration(e.to_string()))?;
+
+        for (version, up_sql) in POSTGRES_MIGRATIONS {
+            let already_applied = txn
+                .query_opt(
+                    &format!("SELECT version FROM {} WHERE version = $1", migrations_table),
+                    &[version],
+                )
+                .await
+                .map_err(|e| StorageError::Migration(e.to_string()))?
+                .is_some();
+
+            if already_applied {
+                continue;
+            }
+
+            let sql = up_sql.replace("{prefix}", &self.table_prefix);
+            txn.batch_execute(&sql)
+                .await
+                .map_err(|e| StorageError::Migration(e.to_string()))?;
+            txn.execute(
+                &format!("INSERT INTO {} (version) VALUES ($1)", migrations_table),
+                &[version],
+            )
+            .await
+            .map_err(|e| StorageError::Migration(e.to_string()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| StorageError::Migration(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn services_table(&self) -> String {
+        format!("{}services", self.table_prefix)
+    }
+
+    fn pattern_index_table(&self) -> String {
+        format!("{}pattern_index", self.table_prefix)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for PostgresStorage {
+    async fn store_service(&self, service_id: String, contract: IntentContract) -> Result<(), StorageError> {
+        let conn = self.pool.get().await.map_err(|e| StorageError::Pool(e.to_string()))?;
+        let contract_data = serde_json::to_value(&contract)
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let existing = conn
+            .query_opt(
+                &format!("SELECT service_id FROM {} WHERE service_id = $1", self.services_table()),
+                &[&service_id],
+            )
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        if existing.is_some() {
+            return Err(StorageError::ServiceAlreadyExists(service_id));
+        }
+
+        conn.execute(
+            &format!("INSERT INTO {} (service_id, contract) VALUES ($1, $2)", self.services_table()),
+            &[&service_id, &contract_data],
+        )
+        .await
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        for pattern in &contract.spec.intent_patterns {
+            conn.execute(
+                &format!(
+                    "INSERT INTO {} (pattern, service_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+                    self.pattern_index_table()
+                ),
+                &[&pattern.pattern.action, &service_id],
+            )
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    async fn get_service(&self, service_id: &str) -> Result<Option<IntentContract>, StorageError> {
+        let conn = self.pool.get().await.map_err(|e| StorageError::Pool(e.to_string()))?;
+        let row = conn
+            .query_opt(
+                &format!("SELECT contract FROM {} WHERE service_id = $1", self.services_table()),
+                &[&service_id],
+            )
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        match row {
+            Some(row) => {
+                let contract_data: serde_json::Value = row.get(0);
+                let contract = serde_json::from_value(contract_data)
+                    .map_err(|e| StorageError::Database(e.to_string()))?;
+                Ok(Some(contract))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_service(&self, service_id: &str) -> Result<(), StorageError> {
+        let conn = self.pool.get().await.map_err(|e| StorageError::Pool(e.to_string()))?;
+        conn.execute(
+            &format!("DELETE FROM {} WHERE service_id = $1", self.pattern_index_table()),
+            &[&service_id],
+        )
+        .await
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+        conn.execute(
+            &format!("DELETE FROM {} WHERE service_id = $1", self.services_table()),
+            &[&service_id],
+        )
+        .await
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+        self.liveness.write().await.remove(service_id);
+        Ok(())
+    }
+
+    async fn get_all_service_ids(&self) -> Result<Vec<String>, StorageError> {
+        let conn = self.pool.get().await.map_err(|e| StorageError::Pool(e.to_string()))?;
+        let rows = conn
+            .query(&format!("SELECT service_id FROM {}", self.services_table()), &[])
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn find_services_by_pattern(&self, pattern: &str) -> Result<Vec<String>, StorageError> {
+        let conn = self.pool.get().await.map_err(|e| StorageError::Pool(e.to_string()))?;
+        let rows = conn
+            .query(
+                &format!("SELECT service_id FROM {} WHERE pattern = $1", self.pattern_index_table()),
+                &[&pattern],
+            )
+            .await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn touch_service(
+        &self,
+        service_id: &str,
+        now: std::time::Instant,
+        _ttl: std::time::Duration,
+    ) -> Result<(), StorageError> {
+        self.liveness.write().await.insert(service_id.to_string(), now);
+        Ok(())
+    }
+
+    async fn reap_expired(&self, ttl: std::time::Duration) -> Result<Vec<String>, StorageError> {
+        let expired: Vec<String> = self
+            .liveness
+            .read()
+            .await
+            .iter()
+            .filter(|(_, last_seen)| last_seen.elapsed() > ttl)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for service_id in &expired {
+            self.delete_service(service_id).await?;
+        }
+
+        Ok(expired)
+    }
+}
+
+/// SQLite存储后端（单机持久化部署）
+pub struct SqliteStorage {
+    conn: Arc<RwLock<rusqlite::Connection>>,
+    // 心跳存活状态是进程内瞬时信号，重启后由下一次心跳重新建立，无需落库
+    liveness: Arc<RwLock<HashMap<String, std::time::Instant>>>,
+}
+
+impl SqliteStorage {
+    pub fn new(path: &str) -> Result<Self, StorageError> {
+        let conn = rusqlite::Connection::open(path)
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS services (service_id TEXT PRIMARY KEY, contract TEXT NOT NULL)",
+            [],
+        )
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        Ok(Self {
+            conn: Arc::new(RwLock::new(conn)),
+            liveness: Arc::new(RwLock::new(HashMap::new())),
+        })
+    }
+}
+
+#[async_trait]
+impl StorageBackend for SqliteStorage {
+    async fn store_service(&self, service_id: String, contract: IntentContract) -> Result<(), StorageError> {
+        let conn = self.conn.write().await;
+        let contract_data = serde_json::to_string(&contract)
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let existing: Option<String> = conn
+            .query_row(
+                "SELECT service_id FROM services WHERE service_id = ?1",
+                [&service_id],
+                |row| row.get(0),
+            )
+            .ok();
+
+        if existing.is_some() {
+            return Err(StorageError::ServiceAlreadyExists(service_id));
+        }
+
+        conn.execute(
+            "INSERT INTO services (service_id, contract) VALUES (?1, ?2)",
+            rusqlite::params![service_id, contract_data],
+        )
+        .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_service(&self, service_id: &str) -> Result<Option<IntentContract>, StorageError> {
+        let conn = self.conn.read().await;
+        let data: Option<String> = conn
+            .query_row(
+                "SELECT contract FROM services WHERE service_id = ?1",
+                [service_id],
+                |row| row.get(0),
+            )
+            .ok();
+
+        match data {
+            Some(data) => {
+                let contract = serde_json::from_str(&data)
+                    .map_err(|e| StorageError::Database(e.to_string()))?;
+                Ok(Some(contract))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_service(&self, service_id: &str) -> Result<(), StorageError> {
+        let conn = self.conn.write().await;
+        conn.execute("DELETE FROM services WHERE service_id = ?1", [service_id])
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        drop(conn);
+        self.liveness.write().await.remove(service_id);
+        Ok(())
+    }
+
+    async fn get_all_service_ids(&self) -> Result<Vec<String>, StorageError> {
+        let conn = self.conn.read().await;
+        let mut stmt = conn
+            .prepare("SELECT service_id FROM services")
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| StorageError::Database(e.to_string()))?
+            .filter_map(Result::ok)
+            .collect();
+        Ok(ids)
+    }
+
+    async fn find_services_by_pattern(&self, pattern: &str) -> Result<Vec<String>, StorageError> {
+        // 简化实现：逐行扫描并在应用层匹配模式，而非维护独立索引表
+        let conn = self.conn.read().await;
+        let mut stmt = conn
+            .prepare("SELECT service_id, contract FROM services")
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let mut matches = Vec::new();
+        for row in rows.filter_map(Result::ok) {
+            let (service_id, contract_data) = row;
+            if let Ok(contract) = serde_json::from_str::<IntentContract>(&contract_data) {
+                if contract
+                    .spec
+                    .intent_patterns
+                    .iter()
+                    .any(|p| p.pattern.action == pattern)
+                {
+                    matches.push(service_id);
+                }
+            }
+        }
+
+        Ok(matches)
+    }
+
+    async fn touch_service(
+        &self,
+        service_id: &str,
+        now: std::time::Instant,
+        _ttl: std::time::Duration,
+    ) -> Result<(), StorageError> {
+        self.liveness.write().await.insert(service_id.to_string(), now);
+        Ok(())
+    }
+
+    async fn reap_expired(&self, ttl: std::time::Duration) -> Result<Vec<String>, StorageError> {
+        let expired: Vec<String> = self
+            .liveness
+            .read()
+            .await
+            .iter()
+            .filter(|(_, last_seen)| last_seen.elapsed() > ttl)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for service_id in &expired {
+            self.delete_service(service_id).await?;
+        }
+
+        Ok(expired)
+    }
+}
+
+/// LMDB存储后端（单机持久化部署，写入吞吐优于SQLite）
+pub struct LmdbStorage {
+    env: heed::Env,
+    db: heed::Database<heed::types::Str, heed::types::Str>,
+    // 心跳存活状态是进程内瞬时信号，重启后由下一次心跳重新建立，无需落库
+    liveness: Arc<RwLock<HashMap<String, std::time::Instant>>>,
+}
+
+impl LmdbStorage {
+    pub fn new(path: &str, map_size_mb: u64) -> Result<Self, StorageError> {
+        std::fs::create_dir_all(path).map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let env = heed::EnvOpenOptions::new()
+            .map_size((map_size_mb as usize) * 1024 * 1024)
+            .open(path)
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let mut wtxn = env.write_txn().map_err(|e| StorageError::Database(e.to_string()))?;
+        let db = env
+            .create_database(&mut wtxn, Some("services"))
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        wtxn.commit().map_err(|e| StorageError::Database(e.to_string()))?;
+
+        Ok(Self {
+            env,
+            db,
+            liveness: Arc::new(RwLock::new(HashMap::new())),
+        })
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LmdbStorage {
+    async fn store_service(&self, service_id: String, contract: IntentContract) -> Result<(), StorageError> {
+        let contract_data = serde_json::to_string(&contract)
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let mut wtxn = self.env.write_txn().map_err(|e| StorageError::Database(e.to_string()))?;
+        if self
+            .db
+            .get(&wtxn, &service_id)
+            .map_err(|e| StorageError::Database(e.to_string()))?
+            .is_some()
+        {
+            return Err(StorageError::ServiceAlreadyExists(service_id));
+        }
+        self.db
+            .put(&mut wtxn, &service_id, &contract_data)
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        wtxn.commit().map_err(|e| StorageError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn get_service(&self, service_id: &str) -> Result<Option<IntentContract>, StorageError> {
+        let rtxn = self.env.read_txn().map_err(|e| StorageError::Database(e.to_string()))?;
+        match self
+            .db
+            .get(&rtxn, service_id)
+            .map_err(|e| StorageError::Database(e.to_string()))?
+        {
+            Some(data) => {
+                let contract = serde_json::from_str(data)
+                    .map_err(|e| StorageError::Database(e.to_string()))?;
+                Ok(Some(contract))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_service(&self, service_id: &str) -> Result<(), StorageError> {
+        let mut wtxn = self.env.write_txn().map_err(|e| StorageError::Database(e.to_string()))?;
+        self.db
+            .delete(&mut wtxn, service_id)
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        wtxn.commit().map_err(|e| StorageError::Database(e.to_string()))?;
+        self.liveness.write().await.remove(service_id);
+        Ok(())
+    }
+
+    async fn get_all_service_ids(&self) -> Result<Vec<String>, StorageError> {
+        let rtxn = self.env.read_txn().map_err(|e| StorageError::Database(e.to_string()))?;
+        let ids = self
+            .db
+            .iter(&rtxn)
+            .map_err(|e| StorageError::Database(e.to_string()))?
+            .filter_map(Result::ok)
+            .map(|(id, _)| id.to_string())
+            .collect();
+        Ok(ids)
+    }
+
+    async fn find_services_by_pattern(&self, pattern: &str) -> Result<Vec<String>, StorageError> {
+        // 简化实现：逐条扫描并在应用层匹配模式，而非维护独立索引数据库
+        let rtxn = self.env.read_txn().map_err(|e| StorageError::Database(e.to_string()))?;
+        let mut matches = Vec::new();
+        for entry in self.db.iter(&rtxn).map_err(|e| StorageError::Database(e.to_string()))? {
+            let (service_id, contract_data) = entry.map_err(|e| StorageError::Database(e.to_string()))?;
+            if let Ok(contract) = serde_json::from_str::<IntentContract>(contract_data) {
+                if contract
+                    .spec
+                    .intent_patterns
+                    .iter()
+                    .any(|p| p.pattern.action == pattern)
+                {
+                    matches.push(service_id.to_string());
+                }
+            }
+        }
+        Ok(matches)
+    }
+
+    async fn touch_service(
+        &self,
+        service_id: &str,
+        now: std::time::Instant,
+        _ttl: std::time::Duration,
+    ) -> Result<(), StorageError> {
+        self.liveness.write().await.insert(service_id.to_string(), now);
+        Ok(())
+    }
+
+    async fn reap_expired(&self, ttl: std::time::Duration) -> Result<Vec<String>, StorageError> {
+        let expired: Vec<String> = self
+            .liveness
+            .read()
+            .await
+            .iter()
+            .filter(|(_, last_seen)| last_seen.elapsed() > ttl)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for service_id in &expired {
+            self.delete_service(service_id).await?;
+        }
+
+        Ok(expired)
+    }
 }
 
 /// Redis存储后端（用于生产环境）
@@ -142,6 +768,11 @@ impl RedisStorage {
     fn pattern_key(&self, pattern: &str) -> String {
         format!("{}:patterns:{}", self.prefix, pattern)
     }
+
+    /// 存活键：设置了 TTL 的哨兵键，存在即表示服务最近发过心跳
+    fn liveness_key(&self, service_id: &str) -> String {
+        format!("{}:liveness:{}", self.prefix, service_id)
+    }
 }
 
 #[async_trait]
@@ -226,12 +857,13 @@ impl StorageBackend for RedisStorage {
         let key = self.service_key(service_id);
         redis::cmd("DEL")
             .arg(&key)
+            .arg(self.liveness_key(service_id))
             .query_async(&mut conn).await
             .map_err(|e| StorageError::Database(e.to_string()))?;
-        
+
         Ok(())
     }
-    
+
     async fn get_all_service_ids(&self) -> Result<Vec<String>, StorageError> {
         let mut conn = self.client.get_async_connection().await
             .map_err(|e| StorageError::Database(e.to_string()))?;
@@ -259,7 +891,214 @@ impl StorageBackend for RedisStorage {
             .arg(&pattern_key)
             .query_async(&mut conn).await
             .map_err(|e| StorageError::Database(e.to_string()))?;
-        
+
         Ok(service_ids)
     }
+
+    /// 在存活哨兵键上 `SETEX`，由 Redis 自身的过期机制驱动存活判断
+    async fn touch_service(
+        &self,
+        service_id: &str,
+        _now: std::time::Instant,
+        ttl: std::time::Duration,
+    ) -> Result<(), StorageError> {
+        let mut conn = self.client.get_async_connection().await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        redis::cmd("SETEX")
+            .arg(self.liveness_key(service_id))
+            .arg(ttl.as_secs().max(1))
+            .arg("1")
+            .query_async(&mut conn).await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// 存活哨兵键过期即代表服务失联；对照一遍服务列表，清理哨兵键已消失的服务及其模式集合
+    async fn reap_expired(&self, _ttl: std::time::Duration) -> Result<Vec<String>, StorageError> {
+        let mut conn = self.client.get_async_connection().await
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        let mut expired = Vec::new();
+        for service_id in self.get_all_service_ids().await? {
+            let alive: bool = redis::cmd("EXISTS")
+                .arg(self.liveness_key(&service_id))
+                .query_async(&mut conn).await
+                .map_err(|e| StorageError::Database(e.to_string()))?;
+
+            if !alive {
+                self.delete_service(&service_id).await?;
+                expired.push(service_id);
+            }
+        }
+
+        Ok(expired)
+    }
+}
+
+/// Sled存储后端（单机持久化部署，嵌入式、无需外部依赖）
+pub struct SledStorage {
+    services: sled::Tree,
+    pattern_index: sled::Tree,
+    // 心跳存活状态是进程内瞬时信号，重启后由下一次心跳重新建立，无需落库
+    liveness: Arc<RwLock<HashMap<String, std::time::Instant>>>,
+}
+
+impl SledStorage {
+    pub fn new(path: &str) -> Result<Self, StorageError> {
+        let db = sled::open(path).map_err(|e| StorageError::Database(e.to_string()))?;
+        let services = db
+            .open_tree("services")
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        let pattern_index = db
+            .open_tree("pattern_index")
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+
+        Ok(Self {
+            services,
+            pattern_index,
+            liveness: Arc::new(RwLock::new(HashMap::new())),
+        })
+    }
+
+    /// 拼接 `action\0service_id` 形式的模式索引键，支持按 action 前缀扫描
+    fn pattern_index_key(action: &str, service_id: &str) -> Vec<u8> {
+        let mut key = action.as_bytes().to_vec();
+        key.push(0);
+        key.extend_from_slice(service_id.as_bytes());
+        key
+    }
+
+    /// 将事务结果映射为 `StorageError`，展开事务中止原因或底层存储错误
+    fn map_transaction_error(
+        err: sled::transaction::TransactionError<StorageError>,
+    ) -> StorageError {
+        match err {
+            sled::transaction::TransactionError::Abort(e) => e,
+            sled::transaction::TransactionError::Storage(e) => StorageError::Database(e.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl StorageBackend for SledStorage {
+    async fn store_service(&self, service_id: String, contract: IntentContract) -> Result<(), StorageError> {
+        let contract_data = serde_json::to_vec(&contract)
+            .map_err(|e| StorageError::Database(e.to_string()))?;
+        let actions: Vec<String> = contract
+            .spec
+            .intent_patterns
+            .iter()
+            .map(|p| p.pattern.action.clone())
+            .collect();
+
+        // 两棵树在同一事务内更新，保证崩溃后模式索引不会与服务存储分叉
+        (&self.services, &self.pattern_index)
+            .transaction(|(services, pattern_index)| {
+                if services.get(service_id.as_bytes())?.is_some() {
+                    return sled::transaction::abort(StorageError::ServiceAlreadyExists(service_id.clone()));
+                }
+
+                services.insert(service_id.as_bytes(), contract_data.as_slice())?;
+                for action in &actions {
+                    pattern_index.insert(Self::pattern_index_key(action, &service_id), &[])?;
+                }
+
+                Ok(())
+            })
+            .map_err(Self::map_transaction_error)
+    }
+
+    async fn get_service(&self, service_id: &str) -> Result<Option<IntentContract>, StorageError> {
+        match self
+            .services
+            .get(service_id.as_bytes())
+            .map_err(|e| StorageError::Database(e.to_string()))?
+        {
+            Some(data) => {
+                let contract = serde_json::from_slice(&data)
+                    .map_err(|e| StorageError::Database(e.to_string()))?;
+                Ok(Some(contract))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_service(&self, service_id: &str) -> Result<(), StorageError> {
+        let service_id = service_id.to_string();
+
+        (&self.services, &self.pattern_index)
+            .transaction(|(services, pattern_index)| {
+                if let Some(data) = services.get(service_id.as_bytes())? {
+                    let contract: IntentContract = serde_json::from_slice(&data).map_err(|e| {
+                        sled::transaction::ConflictableTransactionError::Abort(StorageError::Database(
+                            e.to_string(),
+                        ))
+                    })?;
+
+                    for pattern in &contract.spec.intent_patterns {
+                        pattern_index
+                            .remove(Self::pattern_index_key(&pattern.pattern.action, &service_id))?;
+                    }
+                }
+
+                services.remove(service_id.as_bytes())?;
+                Ok(())
+            })
+            .map_err(Self::map_transaction_error)?;
+
+        self.liveness.write().await.remove(&service_id);
+        Ok(())
+    }
+
+    async fn get_all_service_ids(&self) -> Result<Vec<String>, StorageError> {
+        let mut ids = Vec::new();
+        for entry in self.services.iter() {
+            let (key, _) = entry.map_err(|e| StorageError::Database(e.to_string()))?;
+            ids.push(String::from_utf8_lossy(&key).to_string());
+        }
+        Ok(ids)
+    }
+
+    async fn find_services_by_pattern(&self, pattern: &str) -> Result<Vec<String>, StorageError> {
+        let mut prefix = pattern.as_bytes().to_vec();
+        prefix.push(0);
+
+        let mut matches = Vec::new();
+        for entry in self.pattern_index.scan_prefix(&prefix) {
+            let (key, _) = entry.map_err(|e| StorageError::Database(e.to_string()))?;
+            if let Some(service_id) = key.get(prefix.len()..) {
+                matches.push(String::from_utf8_lossy(service_id).to_string());
+            }
+        }
+        Ok(matches)
+    }
+
+    async fn touch_service(
+        &self,
+        service_id: &str,
+        now: std::time::Instant,
+        _ttl: std::time::Duration,
+    ) -> Result<(), StorageError> {
+        self.liveness.write().await.insert(service_id.to_string(), now);
+        Ok(())
+    }
+
+    async fn reap_expired(&self, ttl: std::time::Duration) -> Result<Vec<String>, StorageError> {
+        let expired: Vec<String> = self
+            .liveness
+            .read()
+            .await
+            .iter()
+            .filter(|(_, last_seen)| last_seen.elapsed() > ttl)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for service_id in &expired {
+            self.delete_service(service_id).await?;
+        }
+
+        Ok(expired)
+    }
 }
\ No newline at end of file
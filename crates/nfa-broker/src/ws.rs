@@ -0,0 +1,166 @@
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
+use nfa_idl::IntentContract;
+use std::collections::HashMap;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use crate::service::{ServiceEventKind, WatchEvent};
+use crate::BrokerService;
+
+type WsSink = SplitSink<WebSocketStream<TcpStream>, Message>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WsError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// 一次 WebSocket 请求帧，镜像 gRPC `IntentBroker` 中对应的方法；字段以 JSON 文本帧传输，
+/// 每个 WS 消息恰好承载一个完整请求，天然免去额外的长度前缀
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsRequest {
+    Register {
+        contract: IntentContract,
+    },
+    Match {
+        action: String,
+        #[serde(default)]
+        parameters: HashMap<String, String>,
+    },
+    List,
+    /// 开启一次长连接订阅：先重放 `since` 之后的历史事件（若提供），再持续推送实时事件，
+    /// 直至客户端断开；与其余请求不同，一次 `Watch` 会产生多个响应帧
+    Watch {
+        action: String,
+        #[serde(default)]
+        since: Option<u64>,
+    },
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsResponse {
+    Registered { service_id: String },
+    Matches { service_ids: Vec<String> },
+    Services { services: Vec<crate::service::ServiceSummary> },
+    WatchEvent { cursor: u64, kind: ServiceEventKind, service_ids: Vec<String> },
+    Error { message: String },
+}
+
+/// 在 `addr` 上监听 WebSocket 连接，与 gRPC server 并行提供注册/匹配/查询能力，
+/// 供浏览器或无法使用 HTTP/2 的客户端接入；每个连接独立处理，互不影响
+pub async fn serve(service: BrokerService, addr: String) -> Result<(), WsError> {
+    let listener = TcpListener::bind(&addr).await?;
+    tracing::info!("WebSocket transport listening on {}", addr);
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let service = service.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, service).await {
+                tracing::warn!("WebSocket connection from {} closed with error: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, service: BrokerService) -> Result<(), WsError> {
+    let ws_stream = tokio_tungstenite::accept_async(stream).await?;
+    let (mut sink, mut stream) = ws_stream.split();
+
+    while let Some(message) = stream.next().await {
+        let message = message?;
+        let Message::Text(text) = message else {
+            continue;
+        };
+
+        match serde_json::from_str::<WsRequest>(&text) {
+            Ok(WsRequest::Watch { action, since }) => {
+                // Watch 独占这条连接的响应流，不再回到请求/响应的一问一答模式
+                watch_loop(&service, &action, since, &mut sink).await?;
+            }
+            Ok(request) => {
+                let response = dispatch(&service, request).await;
+                let payload = serde_json::to_string(&response)?;
+                sink.send(Message::Text(payload)).await?;
+            }
+            Err(e) => {
+                let payload = serde_json::to_string(&WsResponse::Error { message: e.to_string() })?;
+                sink.send(Message::Text(payload)).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 先重放 `since` 之后的历史事件，再持续推送实时事件；订阅者落后而被丢弃的事件直接跳过，
+/// 广播通道关闭或向连接发送失败时结束。实时事件与 gRPC `watch_intent` 来自同一广播通道，
+/// 但 gRPC `watch_intent` 不重放历史事件
+async fn watch_loop(
+    service: &BrokerService,
+    action: &str,
+    since: Option<u64>,
+    sink: &mut WsSink,
+) -> Result<(), WsError> {
+    let (backlog, mut receiver) = service.watch_events(action, since).await;
+    for event in backlog {
+        send_event(sink, &event).await?;
+    }
+
+    loop {
+        match receiver.recv().await {
+            Ok(event) => send_event(sink, &event).await?,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => return Ok(()),
+        }
+    }
+}
+
+async fn send_event(sink: &mut WsSink, event: &WatchEvent) -> Result<(), WsError> {
+    let response = WsResponse::WatchEvent {
+        cursor: event.cursor,
+        kind: event.kind,
+        service_ids: event.service_ids.clone(),
+    };
+    let payload = serde_json::to_string(&response)?;
+    sink.send(Message::Text(payload)).await?;
+    Ok(())
+}
+
+async fn dispatch(service: &BrokerService, request: WsRequest) -> WsResponse {
+    match request {
+        WsRequest::Register { contract } => match nfa_idl::validate_contract(&contract) {
+            Ok(()) => match service.register_intent_raw(contract).await {
+                Ok(service_id) => WsResponse::Registered { service_id },
+                Err(e) => WsResponse::Error { message: e.to_string() },
+            },
+            Err(e) => WsResponse::Error { message: e.to_string() },
+        },
+        WsRequest::Match { action, parameters } => {
+            let parameters = parameters
+                .into_iter()
+                .map(|(k, v)| (k, serde_json::Value::String(v)))
+                .collect();
+            let service_ids = service.match_intent_raw(&action, parameters).await;
+            WsResponse::Matches { service_ids }
+        }
+        WsRequest::List => WsResponse::Services {
+            services: service.list_services().await,
+        },
+        // Watch 在 handle_connection 中已被拦截转入 watch_loop，不会到达这里
+        WsRequest::Watch { .. } => {
+            WsResponse::Error { message: "watch requests are handled separately".to_string() }
+        }
+    }
+}
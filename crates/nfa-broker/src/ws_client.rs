@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use futures::{SinkExt, StreamExt};
+use nfa_idl::IntentContract;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::service::ServiceSummary;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WsClientError {
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("connection closed unexpectedly")]
+    ConnectionClosed,
+
+    #[error("broker returned an error: {0}")]
+    Broker(String),
+
+    #[error("unexpected response for this request")]
+    UnexpectedResponse,
+}
+
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsRequest {
+    Register {
+        contract: IntentContract,
+    },
+    Match {
+        action: String,
+        #[serde(default)]
+        parameters: HashMap<String, String>,
+    },
+    List,
+    Watch {
+        action: String,
+        since: Option<u64>,
+    },
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsResponse {
+    Registered { service_id: String },
+    Matches { service_ids: Vec<String> },
+    Services { services: Vec<ServiceSummary> },
+    WatchEvent { cursor: u64, kind: WsEventKind, service_ids: Vec<String> },
+    Error { message: String },
+}
+
+/// 服务注册状态变更的种类，镜像 broker 端 `ServiceEventKind` 的线上表示
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WsEventKind {
+    Registered,
+    Unregistered,
+}
+
+/// `BrokerClient` 的 WebSocket 等价物：通过 `ws://`/`wss://` 连接 broker 的 WS 传输端点，
+/// 供浏览器或无法使用 HTTP/2 的环境接入；每次调用发送一个 JSON 文本帧并等待一次响应帧
+pub struct WsBrokerClient {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl WsBrokerClient {
+    pub async fn connect(url: &str) -> Result<Self, WsClientError> {
+        let (socket, _response) = tokio_tungstenite::connect_async(url).await?;
+        Ok(Self { socket })
+    }
+
+    pub async fn register_intent(&mut self, contract: IntentContract) -> Result<String, WsClientError> {
+        match self.call(WsRequest::Register { contract }).await? {
+            WsResponse::Registered { service_id } => Ok(service_id),
+            WsResponse::Error { message } => Err(WsClientError::Broker(message)),
+            _ => Err(WsClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn match_intent(
+        &mut self,
+        action: String,
+        parameters: HashMap<String, String>,
+    ) -> Result<Vec<String>, WsClientError> {
+        match self.call(WsRequest::Match { action, parameters }).await? {
+            WsResponse::Matches { service_ids } => Ok(service_ids),
+            WsResponse::Error { message } => Err(WsClientError::Broker(message)),
+            _ => Err(WsClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn list_services(&mut self) -> Result<Vec<ServiceSummary>, WsClientError> {
+        match self.call(WsRequest::List).await? {
+            WsResponse::Services { services } => Ok(services),
+            WsResponse::Error { message } => Err(WsClientError::Broker(message)),
+            _ => Err(WsClientError::UnexpectedResponse),
+        }
+    }
+
+    /// 开启一次长连接订阅：先重放 `since` 之后的历史事件（若提供），再持续推送实时事件；
+    /// 每收到一个事件即调用一次 `on_event(cursor, kind, service_ids)`，直至连接关闭或出错
+    pub async fn watch_intent(
+        &mut self,
+        action: String,
+        since: Option<u64>,
+        mut on_event: impl FnMut(u64, WsEventKind, Vec<String>),
+    ) -> Result<(), WsClientError> {
+        let payload = serde_json::to_string(&WsRequest::Watch { action, since })?;
+        self.socket.send(Message::Text(payload)).await?;
+
+        loop {
+            match self.socket.next().await {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str(&text)? {
+                    WsResponse::WatchEvent { cursor, kind, service_ids } => {
+                        on_event(cursor, kind, service_ids);
+                    }
+                    WsResponse::Error { message } => return Err(WsClientError::Broker(message)),
+                    _ => return Err(WsClientError::UnexpectedResponse),
+                },
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+                None => return Err(WsClientError::ConnectionClosed),
+            }
+        }
+    }
+
+    async fn call(&mut self, request: WsRequest) -> Result<WsResponse, WsClientError> {
+        let payload = serde_json::to_string(&request)?;
+        self.socket.send(Message::Text(payload)).await?;
+
+        loop {
+            match self.socket.next().await {
+                Some(Ok(Message::Text(text))) => return Ok(serde_json::from_str(&text)?),
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+                None => return Err(WsClientError::ConnectionClosed),
+            }
+        }
+    }
+}
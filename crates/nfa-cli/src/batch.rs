@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use futures::StreamExt;
+use nfa_broker::client::BrokerClientError;
+use nfa_broker::BrokerClient;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Error)]
+pub enum BatchError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("failed to parse batch file: {0}")]
+    Parse(String),
+
+    #[error("broker client error: {0}")]
+    Broker(#[from] BrokerClientError),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchQuery {
+    pub action: String,
+    #[serde(default)]
+    pub parameters: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchResult {
+    pub action: String,
+    pub service_ids: Option<Vec<String>>,
+    pub error: Option<String>,
+}
+
+/// 解析批处理输入文件：`.json`/`.yaml`/`.yml` 按数组反序列化，其余按纯文本每行
+/// `<action> [key=value ...]` 解析，key=value 约定与 `--params` 保持一致
+pub fn load_queries(path: &Path) -> Result<Vec<BatchQuery>, BatchError> {
+    let content = std::fs::read_to_string(path)?;
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => {
+            serde_json::from_str(&content).map_err(|e| BatchError::Parse(e.to_string()))
+        }
+        Some("yaml") | Some("yml") => {
+            serde_yaml::from_str(&content).map_err(|e| BatchError::Parse(e.to_string()))
+        }
+        _ => Ok(content.lines().filter(|line| !line.trim().is_empty()).map(parse_line).collect()),
+    }
+}
+
+fn parse_line(line: &str) -> BatchQuery {
+    let mut parts = line.split_whitespace();
+    let action = parts.next().unwrap_or_default().to_string();
+    let parameters = parts
+        .filter_map(|part| part.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    BatchQuery { action, parameters }
+}
+
+/// 持久连接池：预先建立最多 `concurrency` 个与 broker 的连接并在查询之间复用，
+/// 避免批量匹配时为每条查询重新建立连接的开销
+pub struct ClientPool {
+    clients: Vec<Mutex<BrokerClient>>,
+}
+
+impl ClientPool {
+    pub async fn connect(broker: &str, concurrency: usize) -> Result<Self, BatchError> {
+        let concurrency = concurrency.max(1);
+        let mut clients = Vec::with_capacity(concurrency);
+        for _ in 0..concurrency {
+            clients.push(Mutex::new(BrokerClient::connect(broker.to_string()).await?));
+        }
+        Ok(Self { clients })
+    }
+
+    /// 并发执行全部查询：每条查询按索引轮询分配到固定连接上，连接数即为最大并发度
+    pub async fn run(&self, queries: Vec<BatchQuery>) -> Vec<BatchResult> {
+        let pool_size = self.clients.len();
+        futures::stream::iter(queries.into_iter().enumerate())
+            .map(|(i, query)| async move {
+                let mut client = self.clients[i % pool_size].lock().await;
+                let action = query.action.clone();
+                match client.match_intent(&action, query.parameters).await {
+                    Ok(service_ids) => BatchResult { action, service_ids: Some(service_ids), error: None },
+                    Err(e) => BatchResult { action, service_ids: None, error: Some(e.to_string()) },
+                }
+            })
+            .buffer_unordered(pool_size)
+            .collect()
+            .await
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::Transport;
+
+const DEFAULT_BROKER_ADDRESS: &str = "http://localhost:50051";
+
+#[derive(Debug, Error)]
+pub enum CliConfigError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("YAML parsing error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("unknown profile: {0}")]
+    UnknownProfile(String),
+}
+
+/// `~/.nfa/config.yaml`（或 `--config` 指定路径）中声明的具名 profile 集合
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CliConfigFile {
+    /// 未通过 `--profile`/`NFA_PROFILE` 指定时使用的默认 profile 名
+    #[serde(default)]
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProfileConfig {
+    pub broker: Option<String>,
+    pub transport: Option<Transport>,
+    pub identity_key: Option<PathBuf>,
+}
+
+/// 每条子命令实际使用的、已按优先级解析完毕的配置：显式 CLI flag > `--profile` 选中的 profile
+/// > 硬编码默认值
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    pub broker: String,
+    pub transport: Transport,
+    pub identity_key: Option<PathBuf>,
+}
+
+/// 从 `path`（若提供）加载配置文件，否则尝试默认路径 `~/.nfa/config.yaml`；
+/// 两者皆不存在时视为空配置（向后兼容：不配置 profile 时行为与之前完全一致）
+pub fn load_cli_config(path: Option<&Path>) -> Result<CliConfigFile, CliConfigError> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => default_config_path(),
+    };
+
+    if !path.exists() {
+        return Ok(CliConfigFile::default());
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_yaml::from_str(&content)?)
+}
+
+fn default_config_path() -> PathBuf {
+    crate::home_dir().join(".nfa").join("config.yaml")
+}
+
+/// 按优先级合并出最终生效的配置：CLI flag 覆盖 profile 值，profile 值覆盖内置默认值
+pub fn resolve(
+    config: &CliConfigFile,
+    profile: Option<&str>,
+    broker_flag: Option<String>,
+    transport_flag: Option<Transport>,
+    key_flag: Option<PathBuf>,
+) -> Result<ResolvedConfig, CliConfigError> {
+    let profile_name = profile
+        .map(str::to_string)
+        .or_else(|| std::env::var("NFA_PROFILE").ok())
+        .or_else(|| config.default_profile.clone());
+
+    let profile = match profile_name {
+        Some(name) => Some(
+            config
+                .profiles
+                .get(&name)
+                .cloned()
+                .ok_or(CliConfigError::UnknownProfile(name))?,
+        ),
+        None => None,
+    };
+
+    let broker = broker_flag
+        .or_else(|| profile.as_ref().and_then(|p| p.broker.clone()))
+        .unwrap_or_else(|| DEFAULT_BROKER_ADDRESS.to_string());
+
+    let transport = transport_flag
+        .or_else(|| profile.as_ref().and_then(|p| p.transport))
+        .unwrap_or_default();
+
+    let identity_key = key_flag.or_else(|| profile.as_ref().and_then(|p| p.identity_key.clone()));
+
+    Ok(ResolvedConfig {
+        broker,
+        transport,
+        identity_key,
+    })
+}
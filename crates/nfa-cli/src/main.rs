@@ -1,13 +1,55 @@
-use clap::{Parser, Subcommand};
-use nfa_broker::BrokerClient;
+use clap::{Parser, Subcommand, ValueEnum};
+use nfa_broker::{identity::Keypair, ws_client::{WsBrokerClient, WsEventKind}, BrokerClient};
 use nfa_idl::{load_intent_contract, validate_contract};
 use std::path::PathBuf;
 use tonic::transport::Channel;
 
+mod batch;
+mod config;
+mod registry;
+
+use config::ResolvedConfig;
+
+/// 默认的身份密钥路径；可通过 `--key` 或 `NFA_IDENTITY_KEY` 环境变量覆盖
+fn default_key_path() -> PathBuf {
+    if let Ok(path) = std::env::var("NFA_IDENTITY_KEY") {
+        return PathBuf::from(path);
+    }
+    home_dir().join(".nfa").join("identity.key")
+}
+
+/// 简化的 home 目录解析：依次尝试 `HOME`（Unix）与 `USERPROFILE`（Windows），均缺失时回退当前目录
+fn home_dir() -> PathBuf {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("."))
+}
+
+/// 客户端与 broker 通信所用的传输协议
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Transport {
+    /// 基于 tonic 的 HTTP/2 gRPC（默认）
+    #[default]
+    Grpc,
+    /// 基于 `ws://`/`wss://` 的 WebSocket 传输，适用于浏览器或受限网络环境
+    Ws,
+}
+
 #[derive(Parser)]
 #[command(name = "nfa")]
 #[command(about = "NFA Command Line Interface", long_about = None)]
 struct Cli {
+    /// Path to the layered config file declaring named profiles (defaults to `~/.nfa/config.yaml`)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Named profile to resolve `broker`/`transport`/`identity_key` from (falls back to
+    /// `NFA_PROFILE`, then the config file's `default_profile`)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -16,124 +58,265 @@ struct Cli {
 enum Commands {
     /// Register an intent service
     Register {
-        /// Path to the intent contract YAML file
+        /// Path to the intent contract YAML file, or a `nfa://<hash>`/`https://` URI resolved
+        /// against the content-addressed registry
+        #[arg(short, long)]
+        contract: String,
+
+        /// Broker address (overrides the resolved profile)
+        #[arg(short, long)]
+        broker: Option<String>,
+
+        /// Transport used to reach the broker (overrides the resolved profile)
+        #[arg(short, long, value_enum)]
+        transport: Option<Transport>,
+
+        /// Path to the identity key used to sign this registration (overrides the resolved
+        /// profile; falls back to `NFA_IDENTITY_KEY`/`~/.nfa/identity.key`); see `--no-sign`
         #[arg(short, long)]
-        contract: PathBuf,
-        
-        /// Broker address
-        #[arg(short, long, default_value = "http://localhost:50051")]
-        broker: String,
+        key: Option<PathBuf>,
+
+        /// Register without attaching a signature, even if an identity key is available
+        #[arg(long, default_value_t = false)]
+        no_sign: bool,
     },
-    
+
+    /// Generate a Curve25519 identity keypair for signed registrations
+    Keygen {
+        /// Where to write the new private key (defaults to `NFA_IDENTITY_KEY` or
+        /// `~/.nfa/identity.key`)
+        #[arg(short, long)]
+        key: Option<PathBuf>,
+    },
+
     /// List registered services
     List {
-        /// Broker address
-        #[arg(short, long, default_value = "http://localhost:50051")]
-        broker: String,
+        /// Broker address (overrides the resolved profile)
+        #[arg(short, long)]
+        broker: Option<String>,
+
+        /// Transport used to reach the broker (overrides the resolved profile)
+        #[arg(short, long, value_enum)]
+        transport: Option<Transport>,
     },
-    
+
     /// Match an intent
     Match {
         /// Intent action
         #[arg(short, long)]
         action: String,
-        
+
         /// Intent parameters (key=value format)
         #[arg(short, long)]
         params: Vec<String>,
-        
-        /// Broker address
-        #[arg(short, long, default_value = "http://localhost:50051")]
-        broker: String,
+
+        /// Broker address (overrides the resolved profile)
+        #[arg(short, long)]
+        broker: Option<String>,
+
+        /// Transport used to reach the broker (overrides the resolved profile)
+        #[arg(short, long, value_enum)]
+        transport: Option<Transport>,
     },
-    
+
+    /// Stream live intent matches for an action, with optional replay of missed history
+    ///
+    /// Always connects over the WebSocket transport: the gRPC `IntentMatchResponse` message
+    /// has no cursor field to carry, so resumable watch only works against the WS endpoint.
+    Watch {
+        /// Intent action
+        #[arg(short, long)]
+        action: String,
+
+        /// Broker address (overrides the resolved profile)
+        #[arg(short, long)]
+        broker: Option<String>,
+
+        /// Resume from this cursor, replaying missed events before switching to live streaming
+        #[arg(long)]
+        since: Option<u64>,
+    },
+
     /// Validate an intent contract
     Validate {
-        /// Path to the intent contract YAML file
+        /// Path to the intent contract YAML file, or a `nfa://<hash>`/`https://` URI resolved
+        /// against the content-addressed registry
+        #[arg(short, long)]
+        contract: String,
+    },
+
+    /// Validate a contract and publish it to the content-addressed registry
+    Publish {
+        /// Path to the intent contract YAML file to publish
+        file: PathBuf,
+    },
+
+    /// Dispatch many intent queries against a pool of persistent broker connections
+    Batch {
+        /// Path to the queries file: one `action key=value...` per line, or a JSON/YAML array
+        /// of `{action, parameters}` objects
+        file: PathBuf,
+
+        /// Broker address (overrides the resolved profile)
         #[arg(short, long)]
-        contract: PathBuf,
+        broker: Option<String>,
+
+        /// Number of persistent broker connections to pool
+        #[arg(short, long, default_value_t = 4)]
+        concurrency: usize,
     },
-    
+
     /// Start a local development node
     Dev {
         /// Port for the local broker
         #[arg(short, long, default_value_t = 50051)]
         port: u16,
+
+        /// Also accept WebSocket connections on `port + 1` (overrides the resolved profile)
+        #[arg(short, long, value_enum)]
+        transport: Option<Transport>,
     },
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
-    
+    let cli_config = config::load_cli_config(cli.config.as_deref())?;
+    let profile = cli.profile.as_deref();
+
     match cli.command {
-        Commands::Register { contract, broker } => {
-            register_command(contract, broker).await?;
+        Commands::Register { contract, broker, transport, key, no_sign } => {
+            let resolved = config::resolve(&cli_config, profile, broker, transport, key)?;
+            register_command(contract, resolved, no_sign).await?;
+        }
+        Commands::Keygen { key } => {
+            keygen_command(key)?;
+        }
+        Commands::List { broker, transport } => {
+            let resolved = config::resolve(&cli_config, profile, broker, transport, None)?;
+            list_command(resolved).await?;
         }
-        Commands::List { broker } => {
-            list_command(broker).await?;
+        Commands::Match { action, params, broker, transport } => {
+            let resolved = config::resolve(&cli_config, profile, broker, transport, None)?;
+            match_command(action, params, resolved).await?;
         }
-        Commands::Match { action, params, broker } => {
-            match_command(action, params, broker).await?;
+        Commands::Watch { action, broker, since } => {
+            let resolved = config::resolve(&cli_config, profile, broker, None, None)?;
+            watch_command(action, resolved, since).await?;
         }
         Commands::Validate { contract } => {
             validate_command(contract).await?;
         }
-        Commands::Dev { port } => {
-            dev_command(port).await?;
+        Commands::Publish { file } => {
+            publish_command(file).await?;
+        }
+        Commands::Batch { file, broker, concurrency } => {
+            let resolved = config::resolve(&cli_config, profile, broker, None, None)?;
+            batch_command(file, resolved, concurrency).await?;
+        }
+        Commands::Dev { port, transport } => {
+            let resolved = config::resolve(&cli_config, profile, None, transport, None)?;
+            dev_command(port, resolved).await?;
         }
     }
-    
+
     Ok(())
 }
 
-async fn register_command(contract_path: PathBuf, broker_addr: String) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Registering intent service from: {:?}", contract_path);
-    
+async fn register_command(
+    contract_spec: String,
+    resolved: ResolvedConfig,
+    no_sign: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Registering intent service from: {}", contract_spec);
+
+    // `--contract` 可能是本地路径，也可能是需要先下载的 `nfa://<hash>`/`https://` URI
+    let contract_path = registry::resolve_contract_source(&contract_spec).await?;
+
     // 加载和验证契约
     let contract = load_intent_contract(&contract_path)?;
     validate_contract(&contract)?;
-    
+
     println!("Contract validated successfully: {}", contract.metadata.name);
-    
-    // 连接到Broker
-    let mut client = BrokerClient::connect(broker_addr).await?;
-    
-    // 注册服务
-    let response = client.register_intent(contract).await?;
-    println!("Service registered successfully with ID: {}", response.service_id);
-    
+
+    let identity_key = if no_sign {
+        None
+    } else {
+        let key_path = resolved.identity_key.clone().unwrap_or_else(default_key_path);
+        Keypair::load_from_file(&key_path).ok()
+    };
+
+    let service_id = match resolved.transport {
+        Transport::Grpc => {
+            let mut client = BrokerClient::connect(resolved.broker).await?;
+            if let Some(key) = identity_key {
+                println!("Signing registration with identity {}", key.public_key_hex());
+                client = client.with_identity_key(key);
+            }
+            client.register_intent(contract).await?.service_id
+        }
+        Transport::Ws => {
+            // WebSocket 传输尚未接入签名校验（见 ws.rs），直接以匿名身份注册
+            let mut client = WsBrokerClient::connect(&resolved.broker).await?;
+            client.register_intent(contract).await?
+        }
+    };
+    println!("Service registered successfully with ID: {}", service_id);
+
     Ok(())
 }
 
-async fn list_command(broker_addr: String) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Listing registered services from: {}", broker_addr);
-    
-    // 连接到Broker
-    let mut client = BrokerClient::connect(broker_addr).await?;
-    
-    // 获取服务列表
-    let services = client.list_services().await?;
-    
-    if services.is_empty() {
-        println!("No services registered.");
-    } else {
-        println!("Registered services:");
-        for service in services {
-            println!("- {}: {}", service.id, service.name);
+fn keygen_command(key_path: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let key_path = key_path.unwrap_or_else(default_key_path);
+    let keypair = Keypair::generate();
+    keypair.save_to_file(&key_path)?;
+
+    println!("Identity key written to: {:?}", key_path);
+    println!("Public key: {}", keypair.public_key_hex());
+
+    Ok(())
+}
+
+async fn list_command(resolved: ResolvedConfig) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Listing registered services from: {}", resolved.broker);
+
+    match resolved.transport {
+        Transport::Grpc => {
+            let mut client = BrokerClient::connect(resolved.broker).await?;
+            let services = client.list_services().await?;
+            if services.is_empty() {
+                println!("No services registered.");
+            } else {
+                println!("Registered services:");
+                for service in services {
+                    println!("- {}: {}", service.id, service.name);
+                }
+            }
+        }
+        Transport::Ws => {
+            let mut client = WsBrokerClient::connect(&resolved.broker).await?;
+            let services = client.list_services().await?;
+            if services.is_empty() {
+                println!("No services registered.");
+            } else {
+                println!("Registered services:");
+                for service in services {
+                    println!("- {}: {}", service.service_id, service.name);
+                }
+            }
         }
     }
-    
+
     Ok(())
 }
 
 async fn match_command(
     action: String,
     params: Vec<String>,
-    broker_addr: String,
+    resolved: ResolvedConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("Matching intent: {}", action);
-    
+
     // 解析参数
     let mut parameters = std::collections::HashMap::new();
     for param in params {
@@ -142,13 +325,18 @@ async fn match_command(
             parameters.insert(parts[0].to_string(), parts[1].to_string());
         }
     }
-    
-    // 连接到Broker
-    let mut client = BrokerClient::connect(broker_addr).await?;
-    
-    // 匹配意图
-    let matches = client.match_intent(&action, parameters).await?;
-    
+
+    let matches = match resolved.transport {
+        Transport::Grpc => {
+            let mut client = BrokerClient::connect(resolved.broker).await?;
+            client.match_intent(&action, parameters).await?
+        }
+        Transport::Ws => {
+            let mut client = WsBrokerClient::connect(&resolved.broker).await?;
+            client.match_intent(action.clone(), parameters).await?
+        }
+    };
+
     if matches.is_empty() {
         println!("No matching services found.");
     } else {
@@ -157,48 +345,108 @@ async fn match_command(
             println!("- {}", service_id);
         }
     }
-    
+
     Ok(())
 }
 
-async fn validate_command(contract_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Validating intent contract: {:?}", contract_path);
-    
+async fn watch_command(
+    action: String,
+    resolved: ResolvedConfig,
+    since: Option<u64>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Watching intent: {} (broker: {})", action, resolved.broker);
+    if let Some(cursor) = since {
+        println!("Replaying events after cursor {}", cursor);
+    }
+
+    let mut client = WsBrokerClient::connect(&resolved.broker).await?;
+    client
+        .watch_intent(action, since, |cursor, kind, service_ids| {
+            let kind = match kind {
+                WsEventKind::Registered => "registered",
+                WsEventKind::Unregistered => "unregistered",
+            };
+            println!("[{}] {} -> {:?}", cursor, kind, service_ids);
+        })
+        .await?;
+
+    Ok(())
+}
+
+async fn validate_command(contract_spec: String) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Validating intent contract: {}", contract_spec);
+
+    let contract_path = registry::resolve_contract_source(&contract_spec).await?;
+
     // 加载和验证契约
     let contract = load_intent_contract(&contract_path)?;
     validate_contract(&contract)?;
-    
+
     println!("Contract is valid!");
     println!("Name: {}", contract.metadata.name);
     println!("Description: {:?}", contract.metadata.description);
     println!("Patterns: {}", contract.spec.intent_patterns.len());
-    
+
     for pattern in &contract.spec.intent_patterns {
         println!("- Action: {}", pattern.pattern.action);
     }
-    
+
+    Ok(())
+}
+
+async fn publish_command(file: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Publishing intent contract: {:?}", file);
+
+    let hash = registry::publish_contract(&file).await?;
+
+    println!("Published successfully. Content hash: {}", hash);
+    println!("Reference it later with: nfa://{}", hash);
+
     Ok(())
 }
 
-async fn dev_command(port: u16) -> Result<(), Box<dyn std::error::Error>> {
+async fn batch_command(
+    file: PathBuf,
+    resolved: ResolvedConfig,
+    concurrency: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let queries = batch::load_queries(&file)?;
+    eprintln!("Loaded {} queries from {:?}, pooling {} connection(s)", queries.len(), file, concurrency);
+
+    let pool = batch::ClientPool::connect(&resolved.broker, concurrency).await?;
+    let results = pool.run(queries).await;
+
+    for result in results {
+        println!("{}", serde_json::to_string(&result)?);
+    }
+
+    Ok(())
+}
+
+async fn dev_command(port: u16, resolved: ResolvedConfig) -> Result<(), Box<dyn std::error::Error>> {
     println!("Starting local development node on port {}", port);
-    
+
     // 启动本地Broker
     let broker_handle = tokio::spawn(async move {
-        let broker = nfa_broker::Broker::new(&format!("0.0.0.0:{}", port))
+        let mut broker = nfa_broker::Broker::new(&format!("0.0.0.0:{}", port))
             .await
             .expect("Failed to create broker");
+        if matches!(resolved.transport, Transport::Ws) {
+            let ws_port = port + 1;
+            println!("Also accepting WebSocket connections on port {}", ws_port);
+            broker = broker.with_ws_listen_address(format!("0.0.0.0:{}", ws_port));
+        }
         broker.run().await.expect("Broker failed");
     });
-    
+
     println!("Local broker started. Press Ctrl+C to stop.");
-    
+
     // 等待终止信号
     tokio::signal::ctrl_c().await?;
     println!("Shutting down...");
-    
+
     // 停止Broker
     broker_handle.abort();
-    
+
     Ok(())
-}
\ No newline at end of file
+}
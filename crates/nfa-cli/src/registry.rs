@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+const NFA_SCHEME: &str = "nfa://";
+const DEFAULT_REGISTRY_URL: &str = "https://registry.nfa.dev";
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("downloaded content hash {actual} does not match requested hash {expected}")]
+    HashMismatch { expected: String, actual: String },
+}
+
+/// 内容寻址缓存目录（`~/.nfa/cache`），按内容哈希命名，下载结果与 `nfa publish` 的产物都落在此处
+fn cache_dir() -> PathBuf {
+    crate::home_dir().join(".nfa").join("cache")
+}
+
+/// 注册中心地址，可通过 `NFA_REGISTRY_URL` 覆盖；`nfa://<hash>` 解析为该地址下的 `/contracts/<hash>`
+fn registry_base_url() -> String {
+    std::env::var("NFA_REGISTRY_URL").unwrap_or_else(|_| DEFAULT_REGISTRY_URL.to_string())
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// 将 `--contract` 接受的三种来源统一解析为本地文件路径：
+/// - 本地路径：原样返回，不做任何下载
+/// - `nfa://<hash>`：优先读取 `~/.nfa/cache/<hash>`；未命中则从注册中心下载并校验哈希后写入缓存
+/// - `http(s)://`：直接下载，以下载内容的哈希作为缓存 key（无法提前校验，但仍落入同一套内容寻址缓存）
+pub async fn resolve_contract_source(spec: &str) -> Result<PathBuf, RegistryError> {
+    if let Some(hash) = spec.strip_prefix(NFA_SCHEME) {
+        return fetch_by_hash(hash).await;
+    }
+
+    if spec.starts_with("http://") || spec.starts_with("https://") {
+        return fetch_by_url(spec).await;
+    }
+
+    Ok(PathBuf::from(spec))
+}
+
+async fn fetch_by_hash(hash: &str) -> Result<PathBuf, RegistryError> {
+    let cached = cache_dir().join(hash);
+    if cached.exists() {
+        return Ok(cached);
+    }
+
+    let url = format!("{}/contracts/{}", registry_base_url(), hash);
+    let bytes = reqwest::get(url).await?.error_for_status()?.bytes().await?;
+
+    let actual = content_hash(&bytes);
+    if actual != hash {
+        return Err(RegistryError::HashMismatch {
+            expected: hash.to_string(),
+            actual,
+        });
+    }
+
+    write_cached(hash, &bytes)?;
+    Ok(cache_dir().join(hash))
+}
+
+async fn fetch_by_url(url: &str) -> Result<PathBuf, RegistryError> {
+    let bytes = reqwest::get(url).await?.error_for_status()?.bytes().await?;
+    let hash = content_hash(&bytes);
+    write_cached(&hash, &bytes)?;
+    Ok(cache_dir().join(hash))
+}
+
+fn write_cached(hash: &str, bytes: &[u8]) -> Result<(), RegistryError> {
+    let dir = cache_dir();
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join(hash), bytes)?;
+    Ok(())
+}
+
+/// 校验并上传一份契约：先复用 `nfa_idl` 的加载/校验路径确保内容有效，再以其文件内容的哈希
+/// 作为内容寻址 key，PUT 到注册中心；同时写入本地缓存，使随后 `--contract nfa://<hash>` 可直接命中
+pub async fn publish_contract(path: &Path) -> Result<String, RegistryError> {
+    let content = std::fs::read(path)?;
+
+    let contract = nfa_idl::load_intent_contract(path)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+    nfa_idl::validate_contract(&contract)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+
+    let hash = content_hash(&content);
+    let url = format!("{}/contracts/{}", registry_base_url(), hash);
+
+    let client = reqwest::Client::new();
+    client.put(url).body(content.clone()).send().await?.error_for_status()?;
+
+    write_cached(&hash, &content)?;
+    Ok(hash)
+}
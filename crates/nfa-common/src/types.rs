@@ -13,6 +13,15 @@ pub struct NodeResourceInfo {
     pub network_bandwidth: u64,
     pub network_latency: u64,
     pub location: Option<NodeLocation>,
+    pub numa_nodes: Vec<NumaNodeInfo>,
+}
+
+/// 单个 NUMA 节点的 CPU 集合和本地内存，供拓扑感知调度判断局部性
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NumaNodeInfo {
+    pub node_id: u32,
+    pub cpu_set: Vec<u32>,
+    pub local_memory_bytes: u64,
 }
 
 /// 加速器信息
@@ -0,0 +1,65 @@
+use clap::Parser;
+use nfa_broker::{Broker, BrokerClient};
+use nfa_idl::{load_intent_contract, validate_contract};
+use nfa_scheduler::NeuroSymbolicScheduler;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+mod nfa_config;
+
+use nfa_config::load_config;
+
+#[derive(Parser)]
+#[command(name = "nfa-core")]
+#[command(about = "Bring up a broker + scheduler cluster from a single YAML config", long_about = None)]
+struct Cli {
+    /// Path to the cluster configuration YAML file
+    #[arg(short, long)]
+    config: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt::init();
+
+    let cli = Cli::parse();
+    let config = load_config(&cli.config)?;
+
+    let scheduler = Arc::new(NeuroSymbolicScheduler::new(config.scheduling_policy.into()));
+    for node in config.nodes {
+        let node_id = node.node_id.clone();
+        scheduler.set_resource_status(node_id, node.into()).await;
+    }
+
+    let broker_address = config.broker.listen_address.clone();
+    let ntp_servers = config.broker.ntp_servers.clone();
+    let broker_handle = tokio::spawn(async move {
+        let broker = Broker::new(&broker_address)
+            .await
+            .expect("Failed to create broker")
+            .with_ntp_servers(ntp_servers);
+        broker.run().await.expect("Broker failed");
+    });
+
+    // 预注册配置中列出的 Intent Contract，broker 刚启动时略作等待以确保端口已就绪
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    for contract_path in &config.contracts {
+        let contract = load_intent_contract(contract_path)?;
+        validate_contract(&contract)?;
+
+        let mut client = BrokerClient::connect(config.broker.listen_address.clone()).await?;
+        let response = client.register_intent(contract).await?;
+        tracing::info!(
+            "Pre-registered contract from {:?} with service ID: {}",
+            contract_path,
+            response.service_id
+        );
+    }
+
+    tracing::info!("nfa-core cluster is up. Press Ctrl+C to stop.");
+    tokio::signal::ctrl_c().await?;
+    tracing::info!("Shutting down...");
+    broker_handle.abort();
+
+    Ok(())
+}
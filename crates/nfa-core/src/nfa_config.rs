@@ -0,0 +1,157 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use nfa_scheduler::{AcceleratorStatus, ResourceStatus, SchedulingPolicy};
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("YAML parsing error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("Invalid configuration: {0}")]
+    Validation(String),
+}
+
+/// 整个集群的启动配置：调度策略、计算节点、broker 监听地址与待预注册的 Intent Contract
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClusterConfig {
+    pub scheduling_policy: SchedulingPolicyConfig,
+    #[serde(default)]
+    pub nodes: Vec<NodeConfig>,
+    pub broker: BrokerConfig,
+    /// 启动时预注册的 Intent Contract YAML 文件路径
+    #[serde(default)]
+    pub contracts: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulingPolicyConfig {
+    PerformanceFirst,
+    EnergyEfficient,
+    LatencySensitive,
+    CostOptimized,
+}
+
+impl From<SchedulingPolicyConfig> for SchedulingPolicy {
+    fn from(policy: SchedulingPolicyConfig) -> Self {
+        match policy {
+            SchedulingPolicyConfig::PerformanceFirst => SchedulingPolicy::PerformanceFirst,
+            SchedulingPolicyConfig::EnergyEfficient => SchedulingPolicy::EnergyEfficient,
+            SchedulingPolicyConfig::LatencySensitive => SchedulingPolicy::LatencySensitive,
+            SchedulingPolicyConfig::CostOptimized => SchedulingPolicy::CostOptimized,
+        }
+    }
+}
+
+/// 单个计算节点的静态初始资源状态
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeConfig {
+    pub node_id: String,
+    pub total_cpu: f64,
+    #[serde(default)]
+    pub used_cpu: f64,
+    pub total_memory: u64,
+    #[serde(default)]
+    pub used_memory: u64,
+    #[serde(default)]
+    pub accelerators: Vec<AcceleratorConfig>,
+    pub network_bandwidth: u64,
+    #[serde(default)]
+    pub available_bandwidth: Option<u64>,
+    pub average_latency_ms: u64,
+    #[serde(default = "default_price_factor")]
+    pub price_factor: f64,
+}
+
+fn default_price_factor() -> f64 {
+    1.0
+}
+
+impl From<NodeConfig> for ResourceStatus {
+    fn from(node: NodeConfig) -> Self {
+        let available_bandwidth = node.available_bandwidth.unwrap_or(node.network_bandwidth);
+        ResourceStatus {
+            total_cpu: node.total_cpu,
+            used_cpu: node.used_cpu,
+            total_memory: node.total_memory,
+            used_memory: node.used_memory,
+            accelerators: node.accelerators.into_iter().map(Into::into).collect(),
+            network_bandwidth: node.network_bandwidth,
+            available_bandwidth,
+            average_latency_ms: node.average_latency_ms,
+            price_factor: node.price_factor,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AcceleratorConfig {
+    pub kind: String,
+    pub total_units: f64,
+    #[serde(default)]
+    pub used_units: f64,
+    #[serde(default)]
+    pub total_memory: u64,
+    #[serde(default)]
+    pub used_memory: u64,
+}
+
+impl From<AcceleratorConfig> for AcceleratorStatus {
+    fn from(accel: AcceleratorConfig) -> Self {
+        AcceleratorStatus {
+            kind: accel.kind,
+            total_units: accel.total_units,
+            used_units: accel.used_units,
+            total_memory: accel.total_memory,
+            used_memory: accel.used_memory,
+        }
+    }
+}
+
+/// Broker 监听地址与时钟漂移校准所需的 NTP 服务器列表
+#[derive(Debug, Clone, Deserialize)]
+pub struct BrokerConfig {
+    pub listen_address: String,
+    #[serde(default)]
+    pub ntp_servers: Vec<String>,
+}
+
+/// 从 YAML 文件加载并校验集群启动配置
+pub fn load_config<P: AsRef<Path>>(path: P) -> Result<ClusterConfig, ConfigError> {
+    let content = std::fs::read_to_string(path)?;
+    let config: ClusterConfig = serde_yaml::from_str(&content)?;
+    validate_config(&config)?;
+    Ok(config)
+}
+
+/// 校验集群配置的基本合法性
+fn validate_config(config: &ClusterConfig) -> Result<(), ConfigError> {
+    if config.broker.listen_address.is_empty() {
+        return Err(ConfigError::Validation("broker.listen_address cannot be empty".to_string()));
+    }
+
+    for node in &config.nodes {
+        if node.node_id.is_empty() {
+            return Err(ConfigError::Validation("node_id cannot be empty".to_string()));
+        }
+        if node.total_cpu <= 0.0 {
+            return Err(ConfigError::Validation(format!(
+                "node {}: total_cpu must be greater than 0",
+                node.node_id
+            )));
+        }
+        if node.total_memory == 0 {
+            return Err(ConfigError::Validation(format!(
+                "node {}: total_memory must be greater than 0",
+                node.node_id
+            )));
+        }
+    }
+
+    Ok(())
+}
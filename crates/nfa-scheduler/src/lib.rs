@@ -5,6 +5,8 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use thiserror::Error;
 
+pub mod metrics;
+
 #[derive(Debug, Error)]
 pub enum SchedulerError {
     #[error("Resource allocation error: {0}")]
@@ -33,6 +35,18 @@ pub enum SchedulingPolicy {
     CostOptimized,
 }
 
+impl SchedulingPolicy {
+    /// 用作指标标签的稳定字符串表示
+    fn as_label(&self) -> &'static str {
+        match self {
+            SchedulingPolicy::PerformanceFirst => "performance_first",
+            SchedulingPolicy::EnergyEfficient => "energy_efficient",
+            SchedulingPolicy::LatencySensitive => "latency_sensitive",
+            SchedulingPolicy::CostOptimized => "cost_optimized",
+        }
+    }
+}
+
 /// 资源分配请求
 #[derive(Debug, Clone)]
 pub struct ResourceRequest {
@@ -98,6 +112,8 @@ pub struct ResourceStatus {
     pub network_bandwidth: u64,
     pub available_bandwidth: u64,
     pub average_latency_ms: u64,
+    /// 节点级价格因子，`CostOptimized` 策略据此对资源单价加权（1.0 为基准价）
+    pub price_factor: f64,
 }
 
 /// 加速器状态
@@ -110,6 +126,35 @@ pub struct AcceleratorStatus {
     pub used_memory: u64,
 }
 
+/// `EnergyEfficient` 策略装箱调度的安全上限：分配后某节点的利用率超过该值即拒绝
+const ENERGY_EFFICIENT_HIGH_WATER_MARK: f64 = 0.95;
+
+/// 计算假设性放置该请求后，节点在 cpu/memory/（若请求了加速器）加速器维度上的利用率，均归一化到 `[0, 1]`
+fn post_allocation_utilization(status: &ResourceStatus, request: &ResourceRequest) -> Vec<f64> {
+    let mut utils = vec![
+        clamp01((status.used_cpu + request.cpu_units) / status.total_cpu),
+        clamp01((status.used_memory + request.memory_mb) as f64 / status.total_memory as f64),
+    ];
+
+    if let Some(accel_request) = &request.accelerator {
+        if let Some(accel_status) = status
+            .accelerators
+            .iter()
+            .find(|a| a.kind == accel_request.kind)
+        {
+            utils.push(clamp01(
+                (accel_status.used_units + accel_request.units) / accel_status.total_units,
+            ));
+        }
+    }
+
+    utils
+}
+
+fn clamp01(value: f64) -> f64 {
+    value.clamp(0.0, 1.0)
+}
+
 /// 基于规则的调度器实现
 pub struct RuleBasedScheduler {
     policy: SchedulingPolicy,
@@ -130,7 +175,13 @@ impl RuleBasedScheduler {
         self.broker_client = Some(client);
         self
     }
-    
+
+    /// 注册/更新一个计算节点的资源状态，供后续调度决策使用；同一 `node_id` 再次调用会覆盖旧状态
+    pub async fn set_resource_status(&self, node_id: String, status: ResourceStatus) {
+        self.resource_status.write().await.insert(node_id, status);
+    }
+
+
     async fn select_best_node(
         &self,
         resource_request: &ResourceRequest,
@@ -159,44 +210,127 @@ impl RuleBasedScheduler {
         status: &HashMap<String, ResourceStatus>,
         request: &ResourceRequest,
     ) -> Result<String, SchedulerError> {
-        // 实现性能优先的选择逻辑
-        // 这里简化实现，实际中会有更复杂的算法
-        for (node_id, node_status) in status {
-            if self.can_allocate(node_status, request) {
-                return Ok(node_id.clone());
-            }
-        }
-        
-        Err(SchedulerError::ResourceAllocation(
-            "No suitable node found".to_string(),
-        ))
+        // 最大化分配后剩余的资源余量（跨节点分散负载）
+        self.select_by_score(status, request, |_, _| true).await
     }
-    
-    // 其他选择方法的简化实现
+
     async fn select_by_energy_efficiency(
         &self,
         status: &HashMap<String, ResourceStatus>,
         request: &ResourceRequest,
     ) -> Result<String, SchedulerError> {
-        self.select_by_performance(status, request).await
+        // 装箱式调度：向已有负载的节点集中，但不超过高水位线，便于闲置节点下电
+        self.select_by_score(status, request, |node_status, req| {
+            let utils = post_allocation_utilization(node_status, req);
+            let mean = utils.iter().sum::<f64>() / utils.len() as f64;
+            mean <= ENERGY_EFFICIENT_HIGH_WATER_MARK
+        })
+        .await
     }
-    
+
     async fn select_by_latency(
         &self,
         status: &HashMap<String, ResourceStatus>,
         request: &ResourceRequest,
     ) -> Result<String, SchedulerError> {
-        self.select_by_performance(status, request).await
+        // `max_latency_ms` 已在 can_allocate 中硬性校验，这里按延迟从低到高排序
+        self.select_by_score(status, request, |_, _| true).await
     }
-    
+
     async fn select_by_cost(
         &self,
         status: &HashMap<String, ResourceStatus>,
         request: &ResourceRequest,
     ) -> Result<String, SchedulerError> {
-        self.select_by_performance(status, request).await
+        self.select_by_score(status, request, |_, _| true).await
     }
-    
+
+    /// 过滤出可分配该请求的节点（并应用策略专属的额外硬性约束 `extra_gate`），
+    /// 按 `score_node` 打分取最高者；打平时取 `node_id` 字典序较小者，保证确定性
+    async fn select_by_score(
+        &self,
+        status: &HashMap<String, ResourceStatus>,
+        request: &ResourceRequest,
+        extra_gate: impl Fn(&ResourceStatus, &ResourceRequest) -> bool,
+    ) -> Result<String, SchedulerError> {
+        status
+            .iter()
+            .filter(|(_, node_status)| {
+                self.can_allocate(node_status, request) && extra_gate(node_status, request)
+            })
+            .map(|(node_id, node_status)| (node_id, self.score_node(node_status, request)))
+            .fold(None::<(&String, f64)>, |best, (node_id, score)| match best {
+                Some((best_id, best_score))
+                    if best_score > score || (best_score == score && best_id <= node_id) =>
+                {
+                    Some((best_id, best_score))
+                }
+                _ => Some((node_id, score)),
+            })
+            .map(|(node_id, _)| node_id.clone())
+            .ok_or_else(|| SchedulerError::ResourceAllocation("No suitable node found".to_string()))
+    }
+
+    /// 对节点按当前策略打分：分数越高越优先被选中
+    fn score_node(&self, status: &ResourceStatus, request: &ResourceRequest) -> f64 {
+        match self.policy {
+            SchedulingPolicy::PerformanceFirst => {
+                let utils = post_allocation_utilization(status, request);
+                utils.iter().map(|u| 1.0 - u).sum::<f64>() / utils.len() as f64
+            }
+            SchedulingPolicy::EnergyEfficient => {
+                let utils = post_allocation_utilization(status, request);
+                utils.iter().sum::<f64>() / utils.len() as f64
+            }
+            SchedulingPolicy::LatencySensitive => -(status.average_latency_ms as f64),
+            SchedulingPolicy::CostOptimized => -self.estimated_cost(status, request),
+        }
+    }
+
+    /// `schedule` 的实际实现，拆分出来以便在外层统一记录指标（无论成功或失败）
+    async fn schedule_inner(
+        &self,
+        _intent_request: &IntentRequest,
+        resource_request: &ResourceRequest,
+    ) -> Result<ResourceAllocation, SchedulerError> {
+        let node_id = self.select_best_node(resource_request).await?;
+
+        let status = self.resource_status.read().await;
+        let node_status = status.get(&node_id).ok_or_else(|| {
+            SchedulerError::ResourceAllocation(format!(
+                "node {node_id} disappeared during scheduling"
+            ))
+        })?;
+
+        Ok(ResourceAllocation {
+            node_id: node_id.clone(),
+            cpu_units: resource_request.cpu_units,
+            memory_mb: resource_request.memory_mb,
+            accelerator: resource_request.accelerator.as_ref().map(|accel| AcceleratorAllocation {
+                kind: accel.kind.clone(),
+                units: accel.units,
+                memory_mb: accel.memory_mb.unwrap_or(0),
+            }),
+            estimated_latency_ms: node_status.average_latency_ms,
+            cost_units: self.estimated_cost(node_status, resource_request),
+        })
+    }
+
+    /// 分配请求的预估成本：cpu/memory/加速器单位的加权和，乘以节点价格因子
+    fn estimated_cost(&self, status: &ResourceStatus, request: &ResourceRequest) -> f64 {
+        const CPU_PRICE_PER_UNIT: f64 = 1.0;
+        const MEMORY_PRICE_PER_MB: f64 = 0.001;
+        const ACCELERATOR_PRICE_PER_UNIT: f64 = 5.0;
+
+        let mut cost =
+            request.cpu_units * CPU_PRICE_PER_UNIT + request.memory_mb as f64 * MEMORY_PRICE_PER_MB;
+        if let Some(accel_request) = &request.accelerator {
+            cost += accel_request.units * ACCELERATOR_PRICE_PER_UNIT;
+        }
+        cost * status.price_factor
+    }
+
+
     fn can_allocate(&self, status: &ResourceStatus, request: &ResourceRequest) -> bool {
         // 检查CPU
         if status.used_cpu + request.cpu_units > status.total_cpu {
@@ -257,25 +391,24 @@ impl Scheduler for RuleBasedScheduler {
         intent_request: &IntentRequest,
         resource_request: &ResourceRequest,
     ) -> Result<ResourceAllocation, SchedulerError> {
-        let node_id = self.select_best_node(resource_request).await?;
-        
-        // 这里简化实现，实际中会有更复杂的资源分配逻辑
-        Ok(ResourceAllocation {
-            node_id,
-            cpu_units: resource_request.cpu_units,
-            memory_mb: resource_request.memory_mb,
-            accelerator: resource_request.accelerator.as_ref().map(|accel| AcceleratorAllocation {
-                kind: accel.kind.clone(),
-                units: accel.units,
-                memory_mb: accel.memory_mb.unwrap_or(0),
-            }),
-            estimated_latency_ms: 10, // 简化估计
-            cost_units: 1.0, // 简化成本计算
-        })
+        let start = std::time::Instant::now();
+        let policy_label = self.policy.as_label();
+
+        let result = self.schedule_inner(intent_request, resource_request).await;
+
+        crate::metrics::record_schedule_result(
+            policy_label,
+            if result.is_ok() { "success" } else { "failure" },
+            start.elapsed(),
+        );
+
+        result
     }
-    
+
     async fn get_resource_status(&self) -> HashMap<String, ResourceStatus> {
-        self.resource_status.read().await.clone()
+        let status = self.resource_status.read().await.clone();
+        crate::metrics::update_resource_gauges(&status);
+        status
     }
     
     async fn update_policy(&mut self, policy: SchedulingPolicy) {
@@ -295,6 +428,11 @@ impl NeuroSymbolicScheduler {
             rule_based: RuleBasedScheduler::new(policy),
         }
     }
+
+    /// 注册/更新一个计算节点的资源状态；委托给内部的规则调度器
+    pub async fn set_resource_status(&self, node_id: String, status: ResourceStatus) {
+        self.rule_based.set_resource_status(node_id, status).await;
+    }
 }
 
 #[async_trait]
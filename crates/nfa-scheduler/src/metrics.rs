@@ -0,0 +1,125 @@
+use lazy_static::lazy_static;
+use prometheus::{
+    register_counter_vec, register_gauge_vec, register_histogram_vec, CounterVec, Encoder,
+    GaugeVec, HistogramVec, TextEncoder,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::ResourceStatus;
+
+lazy_static! {
+    // 每个节点当前已分配/总计的 CPU 单位
+    pub static ref NODE_CPU_USED: GaugeVec = register_gauge_vec!(
+        "nfa_scheduler_node_cpu_used",
+        "CPU units currently allocated on the node",
+        &["node_id"]
+    ).unwrap();
+    pub static ref NODE_CPU_TOTAL: GaugeVec = register_gauge_vec!(
+        "nfa_scheduler_node_cpu_total",
+        "Total CPU units available on the node",
+        &["node_id"]
+    ).unwrap();
+
+    // 每个节点当前已分配/总计的内存（MB）
+    pub static ref NODE_MEMORY_USED: GaugeVec = register_gauge_vec!(
+        "nfa_scheduler_node_memory_used_mb",
+        "Memory allocated on the node, in MB",
+        &["node_id"]
+    ).unwrap();
+    pub static ref NODE_MEMORY_TOTAL: GaugeVec = register_gauge_vec!(
+        "nfa_scheduler_node_memory_total_mb",
+        "Total memory available on the node, in MB",
+        &["node_id"]
+    ).unwrap();
+
+    // 每个节点每种加速器当前已分配/总计的单位数
+    pub static ref NODE_ACCELERATOR_USED_UNITS: GaugeVec = register_gauge_vec!(
+        "nfa_scheduler_node_accelerator_used_units",
+        "Accelerator units currently allocated on the node",
+        &["node_id", "kind"]
+    ).unwrap();
+    pub static ref NODE_ACCELERATOR_TOTAL_UNITS: GaugeVec = register_gauge_vec!(
+        "nfa_scheduler_node_accelerator_total_units",
+        "Total accelerator units available on the node",
+        &["node_id", "kind"]
+    ).unwrap();
+
+    // 按策略分组的 schedule() 决策延迟
+    pub static ref SCHEDULE_DURATION: HistogramVec = register_histogram_vec!(
+        "nfa_scheduler_schedule_duration_seconds",
+        "Time spent in Scheduler::schedule",
+        &["policy"]
+    ).unwrap();
+
+    // 按策略/结果分组的调度次数（success 或 failure）
+    pub static ref SCHEDULE_RESULTS_TOTAL: CounterVec = register_counter_vec!(
+        "nfa_scheduler_schedule_results_total",
+        "Total number of schedule() outcomes",
+        &["policy", "outcome"]
+    ).unwrap();
+}
+
+/// 强制注册全部调度器指标，通常在进程启动时调用一次
+pub fn init_metrics() {
+    lazy_static::initialize(&NODE_CPU_USED);
+    lazy_static::initialize(&NODE_CPU_TOTAL);
+    lazy_static::initialize(&NODE_MEMORY_USED);
+    lazy_static::initialize(&NODE_MEMORY_TOTAL);
+    lazy_static::initialize(&NODE_ACCELERATOR_USED_UNITS);
+    lazy_static::initialize(&NODE_ACCELERATOR_TOTAL_UNITS);
+    lazy_static::initialize(&SCHEDULE_DURATION);
+    lazy_static::initialize(&SCHEDULE_RESULTS_TOTAL);
+}
+
+/// 按当前资源状态刷新每节点的 cpu/memory/加速器仪表
+pub fn update_resource_gauges(status: &HashMap<String, ResourceStatus>) {
+    for (node_id, node_status) in status {
+        NODE_CPU_USED.with_label_values(&[node_id]).set(node_status.used_cpu);
+        NODE_CPU_TOTAL.with_label_values(&[node_id]).set(node_status.total_cpu);
+        NODE_MEMORY_USED
+            .with_label_values(&[node_id])
+            .set(node_status.used_memory as f64);
+        NODE_MEMORY_TOTAL
+            .with_label_values(&[node_id])
+            .set(node_status.total_memory as f64);
+
+        for accel in &node_status.accelerators {
+            NODE_ACCELERATOR_USED_UNITS
+                .with_label_values(&[node_id, &accel.kind])
+                .set(accel.used_units);
+            NODE_ACCELERATOR_TOTAL_UNITS
+                .with_label_values(&[node_id, &accel.kind])
+                .set(accel.total_units);
+        }
+    }
+}
+
+/// 记录一次 `schedule()` 决策的结果（"success"/"failure"）与耗时
+pub fn record_schedule_result(policy: &str, outcome: &str, duration: Duration) {
+    SCHEDULE_RESULTS_TOTAL.with_label_values(&[policy, outcome]).inc();
+    SCHEDULE_DURATION
+        .with_label_values(&[policy])
+        .observe(duration.as_secs_f64());
+}
+
+/// 指标注册表：包装 Prometheus 文本编码，供调用方挂载到任意 HTTP/tonic 服务
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MetricsRegistry;
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 将当前已采集的全部指标编码为 Prometheus 文本格式
+    pub fn gather_text(&self) -> Result<String, String> {
+        let encoder = TextEncoder::new();
+        let metric_families = prometheus::gather();
+        let mut buffer = Vec::new();
+        encoder
+            .encode(&metric_families, &mut buffer)
+            .map_err(|e| e.to_string())?;
+        String::from_utf8(buffer).map_err(|e| e.to_string())
+    }
+}
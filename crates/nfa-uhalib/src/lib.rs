@@ -1,7 +1,10 @@
 use async_trait::async_trait;
 use nfa_common::types::{AcceleratorInfo, NodeResourceInfo};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::pin::Pin;
 use thiserror::Error;
+use tokio_stream::{Stream, StreamExt};
 
 #[derive(Debug, Error)]
 pub enum UHAError {
@@ -19,6 +22,12 @@ pub enum UHAError {
     
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("restore could not re-place {} of {total} allocations", unplaced.len())]
+    RestoreIncomplete {
+        unplaced: Vec<UnplacedAllocation>,
+        total: usize,
+    },
 }
 
 /// 统一硬件抽象 trait
@@ -42,13 +51,163 @@ pub trait HardwareAbstraction: Send + Sync {
     
     /// 释放设备资源
     async fn release_device(&self, handle: AllocationHandle) -> Result<(), UHAError>;
-    
+
     /// 获取节点资源信息
     async fn get_node_resource_info(&self) -> Result<NodeResourceInfo, UHAError>;
+
+    /// 在某一类型的设备中挑选最适合满足 `request` 的一个，返回其 `device_id`。
+    /// 默认实现基于 `get_devices_by_type`/`get_device_usage` 打分，实现方通常不需要覆盖它。
+    async fn select_device(
+        &self,
+        request: &ResourceRequest,
+        device_type: DeviceType,
+    ) -> Result<String, UHAError> {
+        let candidates = self.get_devices_by_type(device_type).await?;
+        if candidates.is_empty() {
+            return Err(UHAError::DeviceNotFound(format!(
+                "no devices of type {:?}",
+                device_type
+            )));
+        }
+
+        let mut scored = self.rank_candidates(&candidates, request).await;
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        scored.into_iter().map(|(_, id)| id).next().ok_or_else(|| {
+            UHAError::AccessDenied(format!(
+                "no device of type {:?} fits the request",
+                device_type
+            ))
+        })
+    }
+
+    /// 按打分从高到低依次尝试分配，失败（例如被并发请求抢占）时自动回退到下一候选。
+    /// 简化实现：当前 `DeviceInfo` 只建模单一资源池，所以对每个候选设备只有一个"堆"可以重试；
+    /// 一旦设备支持多显存池，这里应当先在设备内部按池的剩余空间从大到小重试，再换下一个候选设备。
+    /// 任何一次失败的尝试都不会改变 `available_resources`（`allocate_device` 只在成功时才扣减）。
+    async fn try_allocate_best(
+        &self,
+        request: &ResourceRequest,
+        device_type: DeviceType,
+    ) -> Result<AllocationHandle, UHAError> {
+        let candidates = self.get_devices_by_type(device_type).await?;
+        let mut scored = self.rank_candidates(&candidates, request).await;
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut last_err = UHAError::AccessDenied(format!(
+            "no device of type {:?} fits the request",
+            device_type
+        ));
+        for (_, device_id) in scored {
+            match self.allocate_device(&device_id, request).await {
+                Ok(handle) => return Ok(handle),
+                Err(e) => last_err = e,
+            }
+        }
+
+        Err(last_err)
+    }
+
+    /// 给候选设备逐个打分，过滤掉放不下这次请求的设备
+    async fn rank_candidates(
+        &self,
+        candidates: &[DeviceInfo],
+        request: &ResourceRequest,
+    ) -> Vec<(f64, String)> {
+        let mut scored = Vec::new();
+        for device in candidates {
+            if let Some(score) = self.score_device(device, request).await {
+                scored.push((score, device.id.clone()));
+            }
+        }
+        scored
+    }
+
+    /// 对单个设备打分：best-fit（浪费的空闲资源越少分越高），
+    /// 接近热/功率上限的设备扣分，vendor 不是 "NFA" 的设备相对虚拟设备加分；
+    /// 共享内存的集成 GPU（如 Intel/Apple 后端发现的设备）与独立加速卡得到相同的加分。
+    /// 候选设备放不下这次请求时返回 `None`。
+    async fn score_device(&self, device: &DeviceInfo, request: &ResourceRequest) -> Option<f64> {
+        let available = &device.available_resources;
+        if available.compute_units < request.compute_units
+            || available.memory_bytes < request.memory_bytes
+        {
+            return None;
+        }
+        if let Some(requested_bandwidth) = request.bandwidth {
+            if available.bandwidth.unwrap_or(0) < requested_bandwidth {
+                return None;
+            }
+        }
+        if let Some(requested_units) = request.specialized_units {
+            if available.specialized_units.unwrap_or(0.0) < requested_units {
+                return None;
+            }
+        }
+
+        // best-fit：空闲资源相对请求的浪费越小越好，减少碎片
+        let mut score = 100.0
+            - (available.compute_units - request.compute_units).max(0.0)
+            - request
+                .specialized_units
+                .map(|requested| (available.specialized_units.unwrap_or(0.0) - requested).max(0.0))
+                .unwrap_or(0.0);
+
+        // 非虚拟设备优于虚拟设备（虚拟硬件抽象把 vendor 标记为 "NFA"），不区分独立卡与共享内存的集成 GPU
+        if device.vendor != "NFA" {
+            score += 50.0;
+        }
+
+        // 请求声明了 NUMA 偏好时，优先选择挂载在同一节点上的设备
+        if let Some(preferred_node) = request.prefer_numa_node {
+            if device
+                .topology
+                .as_ref()
+                .and_then(|t| t.numa_node)
+                .is_some_and(|node| node == preferred_node)
+            {
+                score += 40.0;
+            }
+        }
+
+        // 临近热/功率上限的设备扣分，避免把新负载堆到已经吃紧的设备上
+        if let Ok(usage) = self.get_device_usage(&device.id).await {
+            const THERMAL_THROTTLE_CELSIUS: f32 = 85.0;
+            if let Some(temperature) = usage.temperature {
+                if temperature > THERMAL_THROTTLE_CELSIUS * 0.9 {
+                    score -= 30.0;
+                }
+            }
+            if let (Some(power_usage), Some(power_limit)) = (usage.power_usage, usage.power_limit_watts) {
+                if power_limit > 0.0 && power_usage / power_limit > 0.9 {
+                    score -= 30.0;
+                }
+            }
+        }
+
+        Some(score)
+    }
+
+    /// 订阅设备热插拔事件流。默认实现返回一个永远不产生事件的空流；
+    /// 库存固定不变的后端（例如一次性扫描的实现）无需覆盖它。
+    async fn subscribe_device_events(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = DeviceEvent> + Send>>, UHAError> {
+        let (_tx, rx) = tokio::sync::mpsc::channel(1);
+        Ok(Box::pin(tokio_stream::wrappers::ReceiverStream::new(rx)))
+    }
+}
+
+/// 设备清单发生的变化，供调度器等消费者感知运行时的硬件拓扑变化
+#[derive(Debug, Clone)]
+pub enum DeviceEvent {
+    Added(DeviceInfo),
+    Removed(String),
+    Changed(DeviceInfo),
 }
 
 /// 设备类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DeviceType {
     Cpu,
     Gpu,
@@ -71,10 +230,142 @@ pub struct DeviceInfo {
     pub capabilities: HashMap<String, String>,
     pub total_resources: DeviceResources,
     pub available_resources: DeviceResources,
+    /// 设备在机器内的物理位置；无法探测（虚拟设备、非 Linux 平台等）时为 `None`
+    pub topology: Option<DeviceTopology>,
 }
 
-/// 设备资源
+/// 设备的 NUMA/PCIe 拓扑信息，用于带宽/延迟敏感的放置决策
+#[derive(Debug, Clone, Default)]
+pub struct DeviceTopology {
+    pub pci_address: Option<PciAddress>,
+    pub numa_node: Option<u32>,
+    pub peer_links: Vec<PeerLink>,
+}
+
+/// PCI 域/总线/设备/功能号，形如 `0000:01:00.0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PciAddress {
+    pub domain: u32,
+    pub bus: u32,
+    pub device: u32,
+    pub function: u32,
+}
+
+/// 到另一设备的高速互联链路，例如 NVLink 或同一 PCIe 交换机下的兄弟设备
 #[derive(Debug, Clone)]
+pub struct PeerLink {
+    pub peer_device_id: String,
+    pub link_type: String,
+    pub bandwidth_mbps: u64,
+}
+
+/// 解析形如 `0000:01:00.0`（sysfs）或 `00000000:01:00.0`（NVML）的 PCI 地址字符串
+fn parse_pci_address_str(address: &str) -> Option<PciAddress> {
+    let mut parts = address.split(':');
+    let domain = parts.next()?;
+    let bus = parts.next()?;
+    let device_and_function = parts.next()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    let (device, function) = device_and_function.split_once('.')?;
+
+    Some(PciAddress {
+        domain: u32::from_str_radix(domain, 16).ok()?,
+        bus: u32::from_str_radix(bus, 16).ok()?,
+        device: u32::from_str_radix(device, 16).ok()?,
+        function: function.parse().ok()?,
+    })
+}
+
+/// 读取 `/sys/bus/pci/devices/<addr>/numa_node`；内核用 `-1` 表示没有关联的 NUMA 节点
+fn read_pci_numa_node(address: PciAddress) -> Option<u32> {
+    let path = format!(
+        "/sys/bus/pci/devices/{:04x}:{:02x}:{:02x}.{}/numa_node",
+        address.domain, address.bus, address.device, address.function
+    );
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| content.trim().parse::<i32>().ok())
+        .and_then(|node| u32::try_from(node).ok())
+}
+
+/// 枚举主机的 NUMA 节点及每个节点的 CPU 集合/本地内存，供 `get_node_resource_info` 填充
+#[cfg(target_os = "linux")]
+fn discover_numa_nodes() -> Vec<nfa_common::types::NumaNodeInfo> {
+    let mut nodes = Vec::new();
+    let Ok(entries) = std::fs::read_dir("/sys/devices/system/node") else {
+        return nodes;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(node_id) = name
+            .to_str()
+            .and_then(|n| n.strip_prefix("node"))
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        let cpu_set = std::fs::read_to_string(entry.path().join("cpulist"))
+            .ok()
+            .map(|s| parse_cpu_list(s.trim()))
+            .unwrap_or_default();
+        let local_memory_bytes = std::fs::read_to_string(entry.path().join("meminfo"))
+            .ok()
+            .and_then(|s| parse_numa_meminfo_total_kb(&s))
+            .map(|kb| kb * 1024)
+            .unwrap_or(0);
+
+        nodes.push(nfa_common::types::NumaNodeInfo {
+            node_id,
+            cpu_set,
+            local_memory_bytes,
+        });
+    }
+
+    nodes.sort_by_key(|n| n.node_id);
+    nodes
+}
+
+#[cfg(not(target_os = "linux"))]
+fn discover_numa_nodes() -> Vec<nfa_common::types::NumaNodeInfo> {
+    Vec::new()
+}
+
+/// 解析 sysfs `cpulist` 格式，例如 `0-3,8-11`
+#[cfg(target_os = "linux")]
+fn parse_cpu_list(list: &str) -> Vec<u32> {
+    let mut cpus = Vec::new();
+    for part in list.split(',').filter(|p| !p.is_empty()) {
+        if let Some((start, end)) = part.split_once('-') {
+            if let (Ok(start), Ok(end)) = (start.parse::<u32>(), end.parse::<u32>()) {
+                cpus.extend(start..=end);
+            }
+        } else if let Ok(cpu) = part.parse::<u32>() {
+            cpus.push(cpu);
+        }
+    }
+    cpus
+}
+
+/// 从 `/sys/devices/system/node/nodeN/meminfo` 中解析 `Node N MemTotal: <kB> kB`
+#[cfg(target_os = "linux")]
+fn parse_numa_meminfo_total_kb(content: &str) -> Option<u64> {
+    content.lines().find_map(|line| {
+        line.split("MemTotal:")
+            .nth(1)?
+            .trim()
+            .split_whitespace()
+            .next()?
+            .parse::<u64>()
+            .ok()
+    })
+}
+
+/// 设备资源
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceResources {
     pub compute_units: f64,
     pub memory_bytes: u64,
@@ -82,8 +373,8 @@ pub struct DeviceResources {
     pub specialized_units: Option<f64>,
 }
 
-/// 设备使用情况
-#[derive(Debug, Clone)]
+/// 设备使用情况。非 NVIDIA（或无法采样的）后端保持新增字段为 `None` 即可。
+#[derive(Debug, Clone, Default)]
 pub struct DeviceUsage {
     pub device_id: String,
     pub used_compute: f64,
@@ -91,20 +382,95 @@ pub struct DeviceUsage {
     pub used_bandwidth: Option<u64>,
     pub temperature: Option<f32>,
     pub power_usage: Option<f32>,
+    /// GPU 核心利用率 (0.0 - 1.0)，NVML `utilization_rates().gpu`
+    pub gpu_utilization: Option<f32>,
+    /// 显存带宽利用率 (0.0 - 1.0)，NVML `utilization_rates().memory`
+    pub memory_utilization: Option<f32>,
+    /// 已用/空闲显存（帧缓冲）
+    pub used_framebuffer_bytes: Option<u64>,
+    pub free_framebuffer_bytes: Option<u64>,
+    /// 核心/显存时钟频率
+    pub core_clock_mhz: Option<u32>,
+    pub memory_clock_mhz: Option<u32>,
+    /// 执行限定功率（不同于瞬时功耗 `power_usage`）
+    pub power_limit_watts: Option<f32>,
+    pub fan_speed_percent: Option<u32>,
+    /// PCIe 吞吐（采样区间内的平均速率）
+    pub pcie_tx_bytes_per_sec: Option<u64>,
+    pub pcie_rx_bytes_per_sec: Option<u64>,
+    /// ECC 错误计数（生命周期累计）
+    pub ecc_errors_corrected: Option<u64>,
+    pub ecc_errors_uncorrected: Option<u64>,
 }
 
-/// 资源请求
+/// 一份历史采样点，用于滑动窗口统计
 #[derive(Debug, Clone)]
+struct UsageSample {
+    usage: DeviceUsage,
+    sampled_at: std::time::Instant,
+}
+
+/// 某设备最近若干次采样组成的环形缓冲区
+#[derive(Debug, Clone, Default)]
+pub struct UsageHistory {
+    samples: std::collections::VecDeque<UsageSample>,
+    capacity: usize,
+}
+
+impl UsageHistory {
+    fn new(capacity: usize) -> Self {
+        Self {
+            samples: std::collections::VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    fn push(&mut self, usage: DeviceUsage) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(UsageSample {
+            usage,
+            sampled_at: std::time::Instant::now(),
+        });
+    }
+
+    /// 最近一次采样快照
+    pub fn latest(&self) -> Option<&DeviceUsage> {
+        self.samples.back().map(|s| &s.usage)
+    }
+
+    /// 近期窗口内 GPU 利用率的 (min, max, avg)，若没有可用样本则返回 `None`
+    pub fn gpu_utilization_minmaxavg(&self) -> Option<(f32, f32, f32)> {
+        let values: Vec<f32> = self
+            .samples
+            .iter()
+            .filter_map(|s| s.usage.gpu_utilization)
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        let min = values.iter().cloned().fold(f32::MAX, f32::min);
+        let max = values.iter().cloned().fold(f32::MIN, f32::max);
+        let avg = values.iter().sum::<f32>() / values.len() as f32;
+        Some((min, max, avg))
+    }
+}
+
+/// 资源请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceRequest {
     pub compute_units: f64,
     pub memory_bytes: u64,
     pub bandwidth: Option<u64>,
     pub specialized_units: Option<f64>,
     pub timeout_ms: Option<u64>,
+    /// 优先选择挂载在该 NUMA 节点上的设备，用于带宽/延迟敏感的放置
+    pub prefer_numa_node: Option<u32>,
 }
 
 /// 分配句柄
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AllocationHandle(uuid::Uuid);
 
 impl AllocationHandle {
@@ -119,30 +485,834 @@ impl Default for AllocationHandle {
     }
 }
 
+/// 一次成功预留所对应的记录，`release_device` 据此归还资源
+#[derive(Debug, Clone)]
+pub struct DeviceAllocation {
+    pub device_id: String,
+    pub reserved: DeviceResources,
+    /// 产生这次预留的原始请求，`snapshot` 记录它，`restore` 据此在迁移目标节点上重新 `try_reserve`
+    pub request: ResourceRequest,
+    pub created_at: std::time::Instant,
+}
+
+/// 单条分配的可序列化记录，供 `snapshot`/`restore` 在节点之间迁移预留状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllocationSnapshot {
+    pub handle: AllocationHandle,
+    pub device_id: String,
+    pub reserved: DeviceResources,
+    pub request: ResourceRequest,
+    /// 捕获时刻该分配已经存在的时长（毫秒）；`restore` 据此重建一个近似的 `created_at`
+    pub age_ms: u64,
+}
+
+/// 捕获时刻单个设备的清单摘要，随快照一并保存，供调用方判断目标节点的设备拓扑是否发生了变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceFingerprint {
+    pub device_id: String,
+    pub device_type: DeviceType,
+    pub total_compute_units: f64,
+    pub total_memory_bytes: u64,
+}
+
+/// `UnifiedHardwareAbstraction` 分配状态的完整快照，用于迁移、重启或故障转移后恢复预留
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UhaSnapshot {
+    pub allocations: Vec<AllocationSnapshot>,
+    pub device_fingerprint: Vec<DeviceFingerprint>,
+}
+
+/// `restore` 中无法重新放置的一条分配，连同失败原因一起返回，供调用方决定是否重新调度
+#[derive(Debug, Clone)]
+pub struct UnplacedAllocation {
+    pub handle: AllocationHandle,
+    pub device_id: String,
+    pub reason: String,
+}
+
+/// 超额分配策略，按设备类型配置
+#[derive(Debug, Clone, Copy)]
+pub enum OvercommitPolicy {
+    /// 请求不得超过当前实际可用资源
+    Strict,
+    /// 允许请求的 compute_units 超过当前可用量，最多到 `available * ratio`；
+    /// 显存/带宽/专用单元不受该比例影响，始终按实际可用量严格检查
+    Ratio(f64),
+}
+
+impl OvercommitPolicy {
+    fn effective_capacity(&self, available: f64) -> f64 {
+        match self {
+            OvercommitPolicy::Strict => available,
+            OvercommitPolicy::Ratio(ratio) => available * ratio,
+        }
+    }
+}
+
+/// 默认的按设备类型超额分配策略：计算型设备允许适度超卖，显存/带宽类资源严格限制
+fn default_overcommit_policies() -> HashMap<DeviceType, OvercommitPolicy> {
+    HashMap::from([
+        (DeviceType::Cpu, OvercommitPolicy::Ratio(1.5)),
+        (DeviceType::Gpu, OvercommitPolicy::Strict),
+        (DeviceType::Tpu, OvercommitPolicy::Strict),
+        (DeviceType::Npu, OvercommitPolicy::Strict),
+        (DeviceType::Fpga, OvercommitPolicy::Strict),
+        (DeviceType::Memory, OvercommitPolicy::Strict),
+        (DeviceType::Network, OvercommitPolicy::Ratio(2.0)),
+        (DeviceType::Storage, OvercommitPolicy::Strict),
+    ])
+}
+
+/// 尝试在锁内原子地从 `available` 扣减 `request` 所需的资源；成功则返回本次预留的份额
+fn try_reserve(
+    available: &mut DeviceResources,
+    request: &ResourceRequest,
+    policy: OvercommitPolicy,
+) -> Option<DeviceResources> {
+    if request.compute_units > policy.effective_capacity(available.compute_units) {
+        return None;
+    }
+    // 显存/带宽/专用单元不参与超额分配，避免真正耗尽物理资源
+    if request.memory_bytes > available.memory_bytes {
+        return None;
+    }
+    if let Some(requested_bandwidth) = request.bandwidth {
+        if requested_bandwidth > available.bandwidth.unwrap_or(0) {
+            return None;
+        }
+    }
+    if let Some(requested_units) = request.specialized_units {
+        if requested_units > available.specialized_units.unwrap_or(0.0) {
+            return None;
+        }
+    }
+
+    available.compute_units -= request.compute_units;
+    available.memory_bytes -= request.memory_bytes;
+    if let Some(requested_bandwidth) = request.bandwidth {
+        available.bandwidth = available.bandwidth.map(|b| b - requested_bandwidth);
+    }
+    if let Some(requested_units) = request.specialized_units {
+        available.specialized_units = available.specialized_units.map(|s| s - requested_units);
+    }
+
+    Some(DeviceResources {
+        compute_units: request.compute_units,
+        memory_bytes: request.memory_bytes,
+        bandwidth: request.bandwidth,
+        specialized_units: request.specialized_units,
+    })
+}
+
+/// 释放时把预留份额加回设备的可用资源
+fn release_reservation(available: &mut DeviceResources, reserved: &DeviceResources) {
+    available.compute_units += reserved.compute_units;
+    available.memory_bytes += reserved.memory_bytes;
+    if let Some(bandwidth) = reserved.bandwidth {
+        available.bandwidth = Some(available.bandwidth.unwrap_or(0) + bandwidth);
+    }
+    if let Some(units) = reserved.specialized_units {
+        available.specialized_units = Some(available.specialized_units.unwrap_or(0.0) + units);
+    }
+}
+
+/// 两次轮询之间的等待间隔，用于在 `timeout_ms` 截止前重试分配
+const ALLOCATION_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(20);
+
+/// 单一厂商加速器后端的发现与遥测接口。`UnifiedHardwareAbstraction` 在构造时运行全部注册的
+/// 后端并合并它们发现的设备，`get_device_usage` 之类的查询按 `device_id` 路由给声明了该设备的后端。
+/// 每个后端应当给自己发现的设备 id 加上专属前缀（如 `gpu-`、`rocm-gpu-`），
+/// 这样 `claims` 既能正确路由又不需要真正重新探测硬件。
+#[async_trait]
+pub trait DeviceBackend: Send + Sync {
+    /// 扫描本厂商的全部设备
+    async fn discover(&self) -> Result<Vec<DeviceInfo>, UHAError>;
+
+    /// 查询指定设备的实时使用情况
+    async fn usage(&self, device_id: &str) -> Result<DeviceUsage, UHAError>;
+
+    /// 该 `device_id` 是否由本后端负责
+    fn claims(&self, device_id: &str) -> bool;
+}
+
+/// 组装默认可用的加速器后端集合；具体包含哪些后端取决于编译时启用的 vendor feature
+fn default_backends() -> Vec<Box<dyn DeviceBackend>> {
+    #[allow(unused_mut)]
+    let mut backends: Vec<Box<dyn DeviceBackend>> = Vec::new();
+    #[cfg(feature = "nvidia")]
+    backends.push(Box::new(NvmlBackend));
+    #[cfg(feature = "rocm")]
+    backends.push(Box::new(RocmBackend));
+    #[cfg(feature = "level_zero")]
+    backends.push(Box::new(LevelZeroBackend));
+    #[cfg(feature = "apple_agx")]
+    backends.push(Box::new(AppleAgxBackend));
+    backends
+}
+
+/// NVIDIA 后端，基于 NVML
+#[cfg(feature = "nvidia")]
+pub struct NvmlBackend;
+
+#[cfg(feature = "nvidia")]
+#[async_trait]
+impl DeviceBackend for NvmlBackend {
+    async fn discover(&self) -> Result<Vec<DeviceInfo>, UHAError> {
+        scan_nvidia_gpus()
+    }
+
+    async fn usage(&self, device_id: &str) -> Result<DeviceUsage, UHAError> {
+        sample_nvml_usage(device_id)
+    }
+
+    fn claims(&self, device_id: &str) -> bool {
+        device_id.starts_with("gpu-")
+    }
+}
+
+#[cfg(feature = "nvidia")]
+fn scan_nvidia_gpus() -> Result<Vec<DeviceInfo>, UHAError> {
+    use nvml_wrapper::NVML;
+
+    let mut gpus = Vec::new();
+
+    match NVML::init() {
+        Ok(nvml) => {
+            if let Ok(count) = nvml.device_count() {
+                for i in 0..count {
+                    if let Ok(device) = nvml.device_by_index(i) {
+                        if let (Ok(name), Ok(memory), Ok(_uuid)) =
+                            (device.name(), device.memory_info(), device.uuid())
+                        {
+                            let total_memory = memory.total;
+                            let topology = device.pci_info().ok().map(|pci_info| {
+                                let pci_address = parse_pci_address_str(&pci_info.bus_id);
+                                let numa_node = pci_address.and_then(read_pci_numa_node);
+                                DeviceTopology {
+                                    pci_address,
+                                    numa_node,
+                                    // 简化实现：NVLink 对等设备查询需要逐对调用 NVML 的 nvlink 状态
+                                    // API，这里先留空，后续接入后按 (local, remote) 设备对填充
+                                    peer_links: Vec::new(),
+                                }
+                            });
+
+                            let gpu = DeviceInfo {
+                                id: format!("gpu-{}", i),
+                                name,
+                                device_type: DeviceType::Gpu,
+                                vendor: "NVIDIA".to_string(),
+                                model: "GPU".to_string(),
+                                capabilities: HashMap::from([
+                                    ("cuda_cores".to_string(), "0".to_string()), // 需要实际获取
+                                    ("tensor_cores".to_string(), "0".to_string()), // 需要实际获取
+                                ]),
+                                total_resources: DeviceResources {
+                                    compute_units: 1.0, // 简化表示
+                                    memory_bytes: total_memory,
+                                    bandwidth: None,
+                                    specialized_units: None,
+                                },
+                                available_resources: DeviceResources {
+                                    compute_units: 1.0,
+                                    memory_bytes: total_memory,
+                                    bandwidth: None,
+                                    specialized_units: None,
+                                },
+                                topology,
+                            };
+
+                            gpus.push(gpu);
+                        }
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Failed to initialize NVML: {}", e);
+        }
+    }
+
+    Ok(gpus)
+}
+
+#[cfg(feature = "nvidia")]
+fn sample_nvml_usage(device_id: &str) -> Result<DeviceUsage, UHAError> {
+    use nvml_wrapper::NVML;
+
+    // 简化实现：每次采样重新初始化 NVML 句柄并按索引匹配设备。
+    // 生产实现应当缓存 NVML 句柄和设备索引映射，避免重复初始化开销。
+    let nvml = NVML::init().map_err(|e| UHAError::DriverError(e.to_string()))?;
+    let count = nvml.device_count().map_err(|e| UHAError::DriverError(e.to_string()))?;
+
+    for i in 0..count {
+        let handle = nvml
+            .device_by_index(i)
+            .map_err(|e| UHAError::DriverError(e.to_string()))?;
+        let sysname = format!("gpu-{}", i);
+        if sysname != device_id {
+            continue;
+        }
+
+        let utilization = handle.utilization_rates().ok();
+        let memory_info = handle.memory_info().ok();
+        let temperature = handle
+            .temperature(nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu)
+            .ok();
+        let power_usage = handle.power_usage().ok();
+        let power_limit = handle.enforced_power_limit().ok();
+        let fan_speed = handle.fan_speed(0).ok();
+        let clock_core = handle
+            .clock_info(nvml_wrapper::enum_wrappers::device::Clock::Graphics)
+            .ok();
+        let clock_mem = handle
+            .clock_info(nvml_wrapper::enum_wrappers::device::Clock::Memory)
+            .ok();
+
+        return Ok(DeviceUsage {
+            device_id: device_id.to_string(),
+            used_compute: utilization.as_ref().map(|u| u.gpu as f64 / 100.0).unwrap_or(0.0),
+            used_memory: memory_info.as_ref().map(|m| m.used).unwrap_or(0),
+            used_bandwidth: None,
+            temperature: temperature.map(|t| t as f32),
+            power_usage: power_usage.map(|p| p as f32 / 1000.0),
+            gpu_utilization: utilization.as_ref().map(|u| u.gpu as f32 / 100.0),
+            memory_utilization: utilization.as_ref().map(|u| u.memory as f32 / 100.0),
+            used_framebuffer_bytes: memory_info.as_ref().map(|m| m.used),
+            free_framebuffer_bytes: memory_info.as_ref().map(|m| m.free),
+            core_clock_mhz: clock_core,
+            memory_clock_mhz: clock_mem,
+            power_limit_watts: power_limit.map(|p| p as f32 / 1000.0),
+            fan_speed_percent: fan_speed,
+            pcie_tx_bytes_per_sec: None,
+            pcie_rx_bytes_per_sec: None,
+            ecc_errors_corrected: None,
+            ecc_errors_uncorrected: None,
+        });
+    }
+
+    Err(UHAError::DeviceNotFound(device_id.to_string()))
+}
+
+/// AMD GPU 后端，通过 `rocm-smi --showallinfo --json` 发现设备和读取遥测。
+/// 简化实现：每次调用都新建子进程解析输出，不像 NVML 那样维护常驻句柄；
+/// 宿主机没有安装 ROCm 驱动栈（命令不存在或执行失败）时发现结果为空而不是报错，
+/// 这样没有 AMD 硬件的机器仍然可以正常启动。
+#[cfg(feature = "rocm")]
+pub struct RocmBackend;
+
+#[cfg(feature = "rocm")]
+#[async_trait]
+impl DeviceBackend for RocmBackend {
+    async fn discover(&self) -> Result<Vec<DeviceInfo>, UHAError> {
+        Ok(rocm_smi_showallinfo()
+            .unwrap_or_default()
+            .iter()
+            .map(|(index, card)| rocm_card_to_device_info(*index, card))
+            .collect())
+    }
+
+    async fn usage(&self, device_id: &str) -> Result<DeviceUsage, UHAError> {
+        let index = rocm_gpu_index(device_id)
+            .ok_or_else(|| UHAError::DeviceNotFound(device_id.to_string()))?;
+        let cards =
+            rocm_smi_showallinfo().map_err(|e| UHAError::DriverError(e.to_string()))?;
+        let card = cards
+            .get(&index)
+            .ok_or_else(|| UHAError::DeviceNotFound(device_id.to_string()))?;
+        Ok(rocm_card_to_usage(device_id, card))
+    }
+
+    fn claims(&self, device_id: &str) -> bool {
+        device_id.starts_with("rocm-gpu-")
+    }
+}
+
+#[cfg(feature = "rocm")]
+fn rocm_gpu_index(device_id: &str) -> Option<u32> {
+    device_id.strip_prefix("rocm-gpu-")?.parse().ok()
+}
+
+/// 运行 `rocm-smi --showallinfo --json` 并按卡号索引解析出的原始字段表；
+/// 命令无法启动（例如不存在）时返回 `Err`，执行失败或输出不是预期的 JSON 对象时返回空表
+#[cfg(feature = "rocm")]
+fn rocm_smi_showallinfo() -> std::io::Result<std::collections::BTreeMap<u32, serde_json::Value>> {
+    let output = std::process::Command::new("rocm-smi")
+        .args(["--showallinfo", "--json"])
+        .output()?;
+    if !output.status.success() {
+        return Ok(std::collections::BTreeMap::new());
+    }
+
+    let mut cards = std::collections::BTreeMap::new();
+    if let Ok(serde_json::Value::Object(map)) = serde_json::from_slice(&output.stdout) {
+        for (key, value) in map {
+            if let Some(index) = key.strip_prefix("card").and_then(|s| s.parse::<u32>().ok()) {
+                cards.insert(index, value);
+            }
+        }
+    }
+    Ok(cards)
+}
+
+#[cfg(feature = "rocm")]
+fn rocm_json_u64(card: &serde_json::Value, key: &str) -> Option<u64> {
+    card.get(key)?.as_str()?.trim().parse().ok()
+}
+
+#[cfg(feature = "rocm")]
+fn rocm_json_f64(card: &serde_json::Value, key: &str) -> Option<f64> {
+    card.get(key)?.as_str()?.trim().parse().ok()
+}
+
+#[cfg(feature = "rocm")]
+fn rocm_card_to_device_info(index: u32, card: &serde_json::Value) -> DeviceInfo {
+    let model = card
+        .get("Card series")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Unknown")
+        .to_string();
+    let total_memory = rocm_json_u64(card, "VRAM Total Memory (B)").unwrap_or(0);
+    // ROCm 把计算单元数当作一种"专用单元"暴露给调度器，对应 NVIDIA 侧的 CUDA/tensor 核心数
+    let compute_units = rocm_json_f64(card, "Compute Unit").unwrap_or(1.0);
+
+    DeviceInfo {
+        id: format!("rocm-gpu-{}", index),
+        name: format!("AMD {}", model),
+        device_type: DeviceType::Gpu,
+        vendor: "AMD".to_string(),
+        model,
+        capabilities: HashMap::from([("shader_cores".to_string(), compute_units.to_string())]),
+        total_resources: DeviceResources {
+            compute_units,
+            memory_bytes: total_memory,
+            bandwidth: None,
+            specialized_units: Some(compute_units),
+        },
+        available_resources: DeviceResources {
+            compute_units,
+            memory_bytes: total_memory,
+            bandwidth: None,
+            specialized_units: Some(compute_units),
+        },
+        topology: None,
+    }
+}
+
+#[cfg(feature = "rocm")]
+fn rocm_card_to_usage(device_id: &str, card: &serde_json::Value) -> DeviceUsage {
+    let gpu_use = rocm_json_f64(card, "GPU use (%)").map(|v| (v / 100.0) as f32);
+    let mem_use = rocm_json_f64(card, "GPU memory use (%)").map(|v| (v / 100.0) as f32);
+    let temperature = rocm_json_f64(card, "Temperature (Sensor edge) (C)").map(|v| v as f32);
+    let power_usage = rocm_json_f64(card, "Average Graphics Package Power (W)").map(|v| v as f32);
+    let used_memory = rocm_json_u64(card, "VRAM Total Used Memory (B)").unwrap_or(0);
+
+    DeviceUsage {
+        device_id: device_id.to_string(),
+        used_compute: gpu_use.unwrap_or(0.0) as f64,
+        used_memory,
+        temperature,
+        power_usage,
+        gpu_utilization: gpu_use,
+        memory_utilization: mem_use,
+        ..Default::default()
+    }
+}
+
+/// Intel GPU 后端。真正的 oneAPI Level Zero 遥测需要链接厂商的 `ze_loader` 运行时，
+/// 这里简化为直接读取 i915/xe 内核驱动在 sysfs 下暴露的信息，足以发现设备和读取忙闲度。
+#[cfg(feature = "level_zero")]
+pub struct LevelZeroBackend;
+
+#[cfg(feature = "level_zero")]
+#[async_trait]
+impl DeviceBackend for LevelZeroBackend {
+    async fn discover(&self) -> Result<Vec<DeviceInfo>, UHAError> {
+        Ok(scan_drm_cards_by_vendor("0x8086", "level-zero-gpu", "Intel", "Xe/Arc"))
+    }
+
+    async fn usage(&self, device_id: &str) -> Result<DeviceUsage, UHAError> {
+        let index = device_id
+            .strip_prefix("level-zero-gpu-")
+            .and_then(|s| s.parse::<u32>().ok())
+            .ok_or_else(|| UHAError::DeviceNotFound(device_id.to_string()))?;
+        let busy_percent = std::fs::read_to_string(format!(
+            "/sys/class/drm/card{}/device/gpu_busy_percent",
+            index
+        ))
+        .ok()
+        .and_then(|s| s.trim().parse::<f32>().ok());
+
+        Ok(DeviceUsage {
+            device_id: device_id.to_string(),
+            used_compute: busy_percent.map(|p| p as f64 / 100.0).unwrap_or(0.0),
+            gpu_utilization: busy_percent.map(|p| p / 100.0),
+            ..Default::default()
+        })
+    }
+
+    fn claims(&self, device_id: &str) -> bool {
+        device_id.starts_with("level-zero-gpu-")
+    }
+}
+
+/// Apple Silicon GPU 后端（Asahi Linux 上的 AGX 驱动）。同样走 sysfs 简化路径：
+/// 通过 `uevent` 里的 `DRIVER=asahi` 识别设备；驱动目前没有暴露标准化的忙闲度/显存计数器，
+/// 所以 `usage` 返回全零占用而不是报错。
+#[cfg(feature = "apple_agx")]
+pub struct AppleAgxBackend;
+
+#[cfg(feature = "apple_agx")]
+#[async_trait]
+impl DeviceBackend for AppleAgxBackend {
+    async fn discover(&self) -> Result<Vec<DeviceInfo>, UHAError> {
+        Ok(scan_asahi_drm_cards())
+    }
+
+    async fn usage(&self, device_id: &str) -> Result<DeviceUsage, UHAError> {
+        if !self.claims(device_id) {
+            return Err(UHAError::DeviceNotFound(device_id.to_string()));
+        }
+        Ok(DeviceUsage {
+            device_id: device_id.to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn claims(&self, device_id: &str) -> bool {
+        device_id.starts_with("agx-gpu-")
+    }
+}
+
+/// 枚举 `/sys/class/drm` 下属于给定 PCI vendor id 的显卡节点；跳过 `cardN-<connector>` 这类连接器子节点
+#[cfg(feature = "level_zero")]
+fn scan_drm_cards_by_vendor(
+    vendor_id: &str,
+    id_prefix: &str,
+    vendor_name: &str,
+    model_name: &str,
+) -> Vec<DeviceInfo> {
+    let mut devices = Vec::new();
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return devices;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(index) = name
+            .to_str()
+            .and_then(|n| n.strip_prefix("card"))
+            .filter(|n| !n.contains('-'))
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        let Ok(vendor) = std::fs::read_to_string(entry.path().join("device/vendor")) else {
+            continue;
+        };
+        if vendor.trim() != vendor_id {
+            continue;
+        }
+
+        devices.push(DeviceInfo {
+            id: format!("{}-{}", id_prefix, index),
+            name: format!("{} GPU {}", vendor_name, index),
+            device_type: DeviceType::Gpu,
+            vendor: vendor_name.to_string(),
+            model: model_name.to_string(),
+            capabilities: HashMap::new(),
+            total_resources: DeviceResources {
+                compute_units: 1.0,
+                memory_bytes: 0, // 共享系统内存，没有独立显存总量
+                bandwidth: None,
+                specialized_units: None,
+            },
+            available_resources: DeviceResources {
+                compute_units: 1.0,
+                memory_bytes: 0,
+                bandwidth: None,
+                specialized_units: None,
+            },
+            topology: None,
+        });
+    }
+
+    devices.sort_by(|a, b| a.id.cmp(&b.id));
+    devices
+}
+
+/// Asahi 的 `apple-agx` DRM 驱动没有标准 PCI vendor id（它挂在平台总线上），
+/// 因此按 `uevent` 里的 `DRIVER=asahi` 而不是 vendor id 识别，其余逻辑与 `scan_drm_cards_by_vendor` 一致
+#[cfg(feature = "apple_agx")]
+fn scan_asahi_drm_cards() -> Vec<DeviceInfo> {
+    let mut devices = Vec::new();
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else {
+        return devices;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(index) = name
+            .to_str()
+            .and_then(|n| n.strip_prefix("card"))
+            .filter(|n| !n.contains('-'))
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        let Ok(uevent) = std::fs::read_to_string(entry.path().join("device/uevent")) else {
+            continue;
+        };
+        if !uevent.lines().any(|l| l == "DRIVER=asahi") {
+            continue;
+        }
+
+        devices.push(DeviceInfo {
+            id: format!("agx-gpu-{}", index),
+            name: format!("Apple AGX GPU {}", index),
+            device_type: DeviceType::Gpu,
+            vendor: "Apple".to_string(),
+            model: "AGX".to_string(),
+            capabilities: HashMap::new(),
+            total_resources: DeviceResources {
+                compute_units: 1.0,
+                memory_bytes: 0, // 统一内存架构，没有独立显存总量
+                bandwidth: None,
+                specialized_units: None,
+            },
+            available_resources: DeviceResources {
+                compute_units: 1.0,
+                memory_bytes: 0,
+                bandwidth: None,
+                specialized_units: None,
+            },
+            topology: None,
+        });
+    }
+
+    devices.sort_by(|a, b| a.id.cmp(&b.id));
+    devices
+}
+
 /// 统一硬件抽象实现
 pub struct UnifiedHardwareAbstraction {
-    devices: HashMap<String, DeviceInfo>,
-    allocations: HashMap<AllocationHandle, DeviceAllocation>,
+    devices: tokio::sync::RwLock<HashMap<String, DeviceInfo>>,
+    allocations: tokio::sync::RwLock<HashMap<AllocationHandle, DeviceAllocation>>,
+    overcommit_policies: HashMap<DeviceType, OvercommitPolicy>,
+    /// 最近一次/近期窗口的 DeviceUsage 采样缓存，由后台采集任务填充
+    usage_cache: std::sync::Arc<tokio::sync::RwLock<HashMap<String, UsageHistory>>>,
+    /// 按厂商插拔的加速器发现/遥测后端注册表
+    backends: Vec<Box<dyn DeviceBackend>>,
 }
 
+/// 采集任务保留的历史样本数
+const USAGE_HISTORY_CAPACITY: usize = 60;
+
 impl UnifiedHardwareAbstraction {
-    pub fn new() -> Result<Self, UHAError> {
-        let mut uha = Self {
-            devices: HashMap::new(),
-            allocations: HashMap::new(),
-        };
-        
-        // 扫描并初始化设备
-        uha.scan_devices()?;
-        
-        Ok(uha)
+    pub async fn new() -> Result<Self, UHAError> {
+        let backends = default_backends();
+        let mut devices = Self::discover_devices()?;
+
+        for backend in &backends {
+            match backend.discover().await {
+                Ok(found) => {
+                    for device in found {
+                        devices.insert(device.id.clone(), device);
+                    }
+                }
+                Err(e) => tracing::warn!("accelerator backend discovery failed: {}", e),
+            }
+        }
+
+        Ok(Self {
+            devices: tokio::sync::RwLock::new(devices),
+            allocations: tokio::sync::RwLock::new(HashMap::new()),
+            overcommit_policies: default_overcommit_policies(),
+            usage_cache: std::sync::Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            backends,
+        })
     }
-    
-    fn scan_devices(&mut self) -> Result<(), UHAError> {
+
+    /// 配置某一设备类型的超额分配策略
+    pub fn set_overcommit_policy(&mut self, device_type: DeviceType, policy: OvercommitPolicy) {
+        self.overcommit_policies.insert(device_type, policy);
+    }
+
+    fn overcommit_policy_for(&self, device_type: DeviceType) -> OvercommitPolicy {
+        self.overcommit_policies
+            .get(&device_type)
+            .copied()
+            .unwrap_or(OvercommitPolicy::Strict)
+    }
+
+    /// 捕获当前分配状态的快照：每条存活的预留（保留其 `AllocationHandle`）加上捕获时刻的设备清单摘要。
+    /// 用于在节点重启、故障转移或主动迁移工作负载前，导出可在目标节点上重放的预留状态。
+    pub async fn snapshot(&self) -> Result<UhaSnapshot, UHAError> {
+        let now = std::time::Instant::now();
+        let allocations = self
+            .allocations
+            .read()
+            .await
+            .iter()
+            .map(|(handle, allocation)| AllocationSnapshot {
+                handle: *handle,
+                device_id: allocation.device_id.clone(),
+                reserved: allocation.reserved.clone(),
+                request: allocation.request.clone(),
+                age_ms: now
+                    .saturating_duration_since(allocation.created_at)
+                    .as_millis() as u64,
+            })
+            .collect();
+
+        let device_fingerprint = self
+            .devices
+            .read()
+            .await
+            .values()
+            .map(|device| DeviceFingerprint {
+                device_id: device.id.clone(),
+                device_type: device.device_type,
+                total_compute_units: device.total_resources.compute_units,
+                total_memory_bytes: device.total_resources.memory_bytes,
+            })
+            .collect();
+
+        Ok(UhaSnapshot {
+            allocations,
+            device_fingerprint,
+        })
+    }
+
+    /// 从快照恢复分配状态：丢弃当前的分配表，为快照中的每条记录在本节点上重新 `try_reserve`，
+    /// 保留原始 `AllocationHandle` 以便调用方（例如 broker）的既有引用仍然有效。
+    /// 目标设备不存在或剩余容量不足的分配会被跳过并收集进 `UHAError::RestoreIncomplete`，
+    /// 由调用方决定是否把它们重新提交给调度器。
+    pub async fn restore(&self, snapshot: UhaSnapshot) -> Result<(), UHAError> {
+        let mut devices = self.devices.write().await;
+        let mut allocations = self.allocations.write().await;
+        // 被丢弃的分配先把预留份额归还给设备，避免容量被永久占用
+        for allocation in allocations.values() {
+            if let Some(device) = devices.get_mut(&allocation.device_id) {
+                release_reservation(&mut device.available_resources, &allocation.reserved);
+            }
+        }
+        allocations.clear();
+
+        let now = std::time::Instant::now();
+        let total = snapshot.allocations.len();
+        let mut unplaced = Vec::new();
+
+        for record in snapshot.allocations {
+            let Some(device) = devices.get_mut(&record.device_id) else {
+                unplaced.push(UnplacedAllocation {
+                    handle: record.handle,
+                    device_id: record.device_id,
+                    reason: "device not found on this node".to_string(),
+                });
+                continue;
+            };
+
+            let policy = self.overcommit_policy_for(device.device_type);
+            match try_reserve(&mut device.available_resources, &record.request, policy) {
+                Some(reserved) => {
+                    let created_at = now
+                        .checked_sub(std::time::Duration::from_millis(record.age_ms))
+                        .unwrap_or(now);
+                    allocations.insert(
+                        record.handle,
+                        DeviceAllocation {
+                            device_id: record.device_id,
+                            reserved,
+                            request: record.request,
+                            created_at,
+                        },
+                    );
+                }
+                None => unplaced.push(UnplacedAllocation {
+                    handle: record.handle,
+                    device_id: record.device_id,
+                    reason: "insufficient free capacity".to_string(),
+                }),
+            }
+        }
+
+        if unplaced.is_empty() {
+            Ok(())
+        } else {
+            Err(UHAError::RestoreIncomplete { unplaced, total })
+        }
+    }
+
+    /// 返回最近一次缓存的采样快照（不触发新的 NVML 查询）
+    pub async fn cached_usage(&self, device_id: &str) -> Option<DeviceUsage> {
+        self.usage_cache
+            .read()
+            .await
+            .get(device_id)
+            .and_then(|h| h.latest())
+            .cloned()
+    }
+
+    /// 返回近期窗口内 GPU 利用率的 (min, max, avg)
+    pub async fn usage_window_stats(&self, device_id: &str) -> Option<(f32, f32, f32)> {
+        self.usage_cache
+            .read()
+            .await
+            .get(device_id)
+            .and_then(|h| h.gpu_utilization_minmaxavg())
+    }
+
+    /// 启动后台采样任务：按固定间隔轮询每个设备的实时 DeviceUsage 并写入缓存。
+    /// 轮询而非每次调用都查询 NVML，因为部分计数器（如 PCIe 吞吐）本身就是区间采样值，
+    /// 且频繁查询驱动开销不小。
+    pub fn spawn_usage_collector(self: &std::sync::Arc<Self>, interval: std::time::Duration) {
+        let uha = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let device_ids: Vec<String> = uha.devices.read().await.keys().cloned().collect();
+                for device_id in device_ids {
+                    if let Ok(usage) = uha.sample_device_usage(&device_id).await {
+                        let mut cache = uha.usage_cache.write().await;
+                        cache
+                            .entry(device_id)
+                            .or_insert_with(|| UsageHistory::new(USAGE_HISTORY_CAPACITY))
+                            .push(usage);
+                    }
+                }
+            }
+        });
+    }
+
+    /// 直接查询一次设备的实时使用情况，不经过缓存：优先路由给注册表中声明了该设备的后端，
+    /// 没有后端声明该设备（例如 CPU，或者编译期没有启用对应厂商 feature）时退化为静态零值
+    async fn sample_device_usage(&self, device_id: &str) -> Result<DeviceUsage, UHAError> {
+        if !self.devices.read().await.contains_key(device_id) {
+            return Err(UHAError::DeviceNotFound(device_id.to_string()));
+        }
+
+        for backend in &self.backends {
+            if backend.claims(device_id) {
+                return backend.usage(device_id).await;
+            }
+        }
+
+        Ok(DeviceUsage {
+            device_id: device_id.to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn discover_devices() -> Result<HashMap<String, DeviceInfo>, UHAError> {
         // 这里简化实现，实际中会调用平台特定的设备发现
-        
+        let mut devices = HashMap::new();
+
         // 添加CPU设备
-        self.devices.insert(
+        devices.insert(
             "cpu-0".to_string(),
             DeviceInfo {
                 id: "cpu-0".to_string(),
@@ -167,113 +1337,39 @@ impl UnifiedHardwareAbstraction {
                     bandwidth: None,
                     specialized_units: None,
                 },
+                topology: None,
             },
         );
-        
-        // 添加GPU设备（如果可用）
-        if cfg!(feature = "nvidia") {
-            if let Ok(gpu_info) = self.scan_nvidia_gpus() {
-                for gpu in gpu_info {
-                    self.devices.insert(gpu.id.clone(), gpu);
-                }
-            }
-        }
-        
-        Ok(())
-    }
-    
-    #[cfg(feature = "nvidia")]
-    fn scan_nvidia_gpus(&self) -> Result<Vec<DeviceInfo>, UHAError> {
-        use nvml_wrapper::NVML;
-        
-        let mut gpus = Vec::new();
-        
-        match NVML::init() {
-            Ok(nvml) => {
-                if let Ok(count) = nvml.device_count() {
-                    for i in 0..count {
-                        if let Ok(device) = nvml.device_by_index(i) {
-                            if let (Ok(name), Ok(memory), Ok(uuid)) = (
-                                device.name(),
-                                device.memory_info(),
-                                device.uuid(),
-                            ) {
-                                let total_memory = memory.total;
-                                
-                                let gpu = DeviceInfo {
-                                    id: format!("gpu-{}", i),
-                                    name,
-                                    device_type: DeviceType::Gpu,
-                                    vendor: "NVIDIA".to_string(),
-                                    model: "GPU".to_string(),
-                                    capabilities: HashMap::from([
-                                        ("cuda_cores".to_string(), "0".to_string()), // 需要实际获取
-                                        ("tensor_cores".to_string(), "0".to_string()), // 需要实际获取
-                                    ]),
-                                    total_resources: DeviceResources {
-                                        compute_units: 1.0, // 简化表示
-                                        memory_bytes: total_memory,
-                                        bandwidth: None,
-                                        specialized_units: None,
-                                    },
-                                    available_resources: DeviceResources {
-                                        compute_units: 1.0,
-                                        memory_bytes: total_memory,
-                                        bandwidth: None,
-                                        specialized_units: None,
-                                    },
-                                };
-                                
-                                gpus.push(gpu);
-                            }
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::warn!("Failed to initialize NVML: {}", e);
-            }
-        }
-        
-        Ok(gpus)
-    }
-    
-    #[cfg(not(feature = "nvidia"))]
-    fn scan_nvidia_gpus(&self) -> Result<Vec<DeviceInfo>, UHAError> {
-        Ok(Vec::new())
+
+        // 其余厂商的加速器由 `backends` 注册表在 `new()` 中发现并合并进来
+        Ok(devices)
     }
 }
 
 #[async_trait]
 impl HardwareAbstraction for UnifiedHardwareAbstraction {
     async fn get_all_devices(&self) -> Result<Vec<DeviceInfo>, UHAError> {
-        Ok(self.devices.values().cloned().collect())
+        Ok(self.devices.read().await.values().cloned().collect())
     }
-    
+
     async fn get_devices_by_type(&self, device_type: DeviceType) -> Result<Vec<DeviceInfo>, UHAError> {
         Ok(self
             .devices
+            .read()
+            .await
             .values()
             .filter(|d| d.device_type == device_type)
             .cloned()
             .collect())
     }
-    
+
     async fn get_device_usage(&self, device_id: &str) -> Result<DeviceUsage, UHAError> {
-        let device = self
-            .devices
-            .get(device_id)
-            .ok_or_else(|| UHAError::DeviceNotFound(device_id.to_string()))?;
-        
-        // 简化实现，实际中会查询设备实际使用情况
-        Ok(DeviceUsage {
-            device_id: device_id.to_string(),
-            used_compute: 0.0,
-            used_memory: 0,
-            used_bandwidth: None,
-            temperature: None,
-            power_usage: None,
-        })
+        // 优先返回后台采集任务缓存的最新样本，没有缓存（采集任务尚未启动或刚启动）时才现场查询一次
+        if let Some(usage) = self.cached_usage(device_id).await {
+            return Ok(usage);
+        }
+
+        self.sample_device_usage(device_id).await
     }
     
     async fn allocate_device(
@@ -281,24 +1377,64 @@ impl HardwareAbstraction for UnifiedHardwareAbstraction {
         device_id: &str,
         resource_request: &ResourceRequest,
     ) -> Result<AllocationHandle, UHAError> {
-        let _device = self
-            .devices
-            .get(device_id)
-            .ok_or_else(|| UHAError::DeviceNotFound(device_id.to_string()))?;
-        
-        // 检查资源是否足够（简化实现）
-        // 实际中会检查设备的可用资源
-        
-        let handle = AllocationHandle::new();
-        
-        Ok(handle)
+        let deadline = resource_request
+            .timeout_ms
+            .map(|ms| std::time::Instant::now() + std::time::Duration::from_millis(ms));
+
+        loop {
+            {
+                let mut devices = self.devices.write().await;
+                let device = devices
+                    .get_mut(device_id)
+                    .ok_or_else(|| UHAError::DeviceNotFound(device_id.to_string()))?;
+                let policy = self.overcommit_policy_for(device.device_type);
+
+                if let Some(reserved) =
+                    try_reserve(&mut device.available_resources, resource_request, policy)
+                {
+                    let handle = AllocationHandle::new();
+                    self.allocations.write().await.insert(
+                        handle,
+                        DeviceAllocation {
+                            device_id: device_id.to_string(),
+                            reserved,
+                            request: resource_request.clone(),
+                            created_at: std::time::Instant::now(),
+                        },
+                    );
+                    return Ok(handle);
+                }
+            }
+
+            match deadline {
+                Some(deadline) if std::time::Instant::now() < deadline => {
+                    tokio::time::sleep(ALLOCATION_RETRY_INTERVAL).await;
+                }
+                _ => {
+                    return Err(UHAError::AccessDenied(format!(
+                        "insufficient resources on device {}",
+                        device_id
+                    )));
+                }
+            }
+        }
     }
-    
-    async fn release_device(&self, _handle: AllocationHandle) -> Result<(), UHAError> {
-        // 释放资源（简化实现）
+
+    async fn release_device(&self, handle: AllocationHandle) -> Result<(), UHAError> {
+        let allocation = self
+            .allocations
+            .write()
+            .await
+            .remove(&handle)
+            .ok_or_else(|| UHAError::AccessDenied("unknown allocation handle".to_string()))?;
+
+        if let Some(device) = self.devices.write().await.get_mut(&allocation.device_id) {
+            release_reservation(&mut device.available_resources, &allocation.reserved);
+        }
+
         Ok(())
     }
-    
+
     async fn get_node_resource_info(&self) -> Result<NodeResourceInfo, UHAError> {
         // 获取系统总资源信息（简化实现）
         Ok(NodeResourceInfo {
@@ -311,6 +1447,7 @@ impl HardwareAbstraction for UnifiedHardwareAbstraction {
             network_bandwidth: 1000, // 1Gbps
             network_latency: 1,      // 1ms
             location: None,
+            numa_nodes: discover_numa_nodes(),
         })
     }
 }
@@ -323,15 +1460,105 @@ pub mod linux {
     /// Linux特定的硬件抽象实现
     pub struct LinuxHardwareAbstraction {
         udev: Option<libudev::Context>,
+        /// 最近一次 scan_udev_devices 或 udev 监控事件反映的设备清单
+        devices: std::sync::Arc<tokio::sync::RwLock<HashMap<String, DeviceInfo>>>,
     }
-    
+
     impl LinuxHardwareAbstraction {
         pub fn new() -> Result<Self, UHAError> {
             let udev = libudev::Context::new().ok();
-            
-            Ok(Self { udev })
+            let uha = Self {
+                udev,
+                devices: std::sync::Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            };
+
+            let initial = uha.scan_udev_devices()?;
+            {
+                // 构造期间还没有并发访问者，try_write 不需要运行在 tokio runtime 之上
+                let mut guard = uha
+                    .devices
+                    .try_write()
+                    .expect("device map cannot be contended during construction");
+                for device in initial {
+                    guard.insert(device.id.clone(), device);
+                }
+            }
+
+            Ok(uha)
         }
-        
+
+        /// 返回最近一次已知的设备清单（一次性扫描结果 + 后续 udev 事件带来的增量更新）
+        pub async fn get_all_devices(&self) -> Vec<DeviceInfo> {
+            self.devices.read().await.values().cloned().collect()
+        }
+
+        /// 订阅 `drm`/`pci`/加速器子系统的 udev 热插拔事件，并据此更新内部设备清单。
+        /// udev 的 `Monitor` API 是阻塞式的，因此在独立线程里轮询，通过 channel 转发给异步消费者，
+        /// 与 `ConfigWatcher` 桥接 `notify` 回调的方式一致。
+        pub fn subscribe_device_events(
+            &self,
+        ) -> Result<Pin<Box<dyn Stream<Item = DeviceEvent> + Send>>, UHAError> {
+            let context = self
+                .udev
+                .as_ref()
+                .ok_or_else(|| UHAError::UnsupportedPlatform("udev context unavailable".to_string()))?;
+
+            let mut builder = libudev::MonitorBuilder::new(context).map_err(|e| {
+                UHAError::Io(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+            })?;
+            for subsystem in ["drm", "pci", "accel"] {
+                builder = builder.match_subsystem(subsystem).map_err(|e| {
+                    UHAError::Io(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+                })?;
+            }
+            let mut monitor = builder.listen().map_err(|e| {
+                UHAError::Io(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+            })?;
+
+            let (tx, rx) = tokio::sync::mpsc::channel(64);
+            let devices = self.devices.clone();
+            let runtime = tokio::runtime::Handle::current();
+            std::thread::spawn(move || {
+                loop {
+                    match monitor.next() {
+                        Some(event) => {
+                            let Some(device) = uevent_to_device_info(&event) else {
+                                continue;
+                            };
+                            let uevent = match event.event_type() {
+                                libudev::EventType::Add => DeviceEvent::Added(device),
+                                libudev::EventType::Remove => DeviceEvent::Removed(device.id),
+                                _ => DeviceEvent::Changed(device),
+                            };
+
+                            let devices = devices.clone();
+                            let uevent_for_cache = uevent.clone();
+                            runtime.block_on(async move {
+                                let mut guard = devices.write().await;
+                                match uevent_for_cache {
+                                    DeviceEvent::Added(d) | DeviceEvent::Changed(d) => {
+                                        guard.insert(d.id.clone(), d);
+                                    }
+                                    DeviceEvent::Removed(id) => {
+                                        guard.remove(&id);
+                                    }
+                                }
+                            });
+
+                            if tx.blocking_send(uevent).is_err() {
+                                break;
+                            }
+                        }
+                        None => {
+                            std::thread::sleep(std::time::Duration::from_millis(200));
+                        }
+                    }
+                }
+            });
+
+            Ok(Box::pin(tokio_stream::wrappers::ReceiverStream::new(rx)))
+        }
+
         pub fn scan_udev_devices(&self) -> Result<Vec<DeviceInfo>, UHAError> {
             let mut devices = Vec::new();
             
@@ -384,8 +1611,9 @@ pub mod linux {
                                     bandwidth: None,
                                     specialized_units: None,
                                 },
+                                topology: pci_topology_from_udev(&device),
                             };
-                            
+
                             devices.push(gpu);
                         }
                     }
@@ -395,14 +1623,82 @@ pub mod linux {
             Ok(devices)
         }
     }
+
+    /// 把一条 udev 事件转换为 `DeviceInfo`，复用 `scan_udev_devices` 识别 GPU 的规则；
+    /// 非 drm/GPU 相关事件（没有 sysname/devtype，或不是 `card*` drm_minor）返回 `None`，由调用方忽略
+    fn uevent_to_device_info(event: &libudev::Event) -> Option<DeviceInfo> {
+        let device = event.device();
+        let sysname = device.sysname()?;
+        let devtype = device.devtype()?;
+        if !(sysname.to_string_lossy().contains("card") && devtype == "drm_minor") {
+            return None;
+        }
+
+        let vendor = device
+            .property_value("ID_VENDOR_FROM_DATABASE")
+            .and_then(|v| v.to_str())
+            .unwrap_or("Unknown")
+            .to_string();
+        let model = device
+            .property_value("ID_MODEL_FROM_DATABASE")
+            .and_then(|v| v.to_str())
+            .unwrap_or("Unknown")
+            .to_string();
+
+        let topology = pci_topology_from_udev(&device);
+
+        Some(DeviceInfo {
+            id: sysname.to_string_lossy().to_string(),
+            name: format!("{} {}", vendor, model),
+            device_type: DeviceType::Gpu,
+            vendor,
+            model,
+            capabilities: HashMap::new(),
+            total_resources: DeviceResources {
+                compute_units: 1.0,
+                memory_bytes: 0,
+                bandwidth: None,
+                specialized_units: None,
+            },
+            available_resources: DeviceResources {
+                compute_units: 1.0,
+                memory_bytes: 0,
+                bandwidth: None,
+                specialized_units: None,
+            },
+            topology,
+        })
+    }
+
+    /// 通过 udev 的父设备关系找到所属的 PCI 设备并解析其拓扑（PCI 地址 + NUMA 节点）
+    fn pci_topology_from_udev(device: &libudev::Device) -> Option<DeviceTopology> {
+        let pci_device = device.parent_with_subsystem("pci").ok().flatten()?;
+        let sysname = pci_device.sysname()?.to_str()?;
+        let pci_address = parse_pci_address_str(sysname)?;
+        let numa_node = read_pci_numa_node(pci_address);
+
+        Some(DeviceTopology {
+            pci_address: Some(pci_address),
+            numa_node,
+            // 简化实现：同 NVML 路径一样，暂不解析 NVLink/PCIe 交换机兄弟设备
+            peer_links: Vec::new(),
+        })
+    }
 }
 
 /// 虚拟硬件抽象（用于测试和开发）
 pub struct VirtualHardwareAbstraction {
     devices: HashMap<String, DeviceInfo>,
+    /// 供调用方（通常是测试）注入模拟热插拔事件的广播发送端
+    events: tokio::sync::broadcast::Sender<DeviceEvent>,
 }
 
 impl VirtualHardwareAbstraction {
+    /// 返回事件发送端的克隆，调用 `.send(DeviceEvent::Added(..))` 即可模拟热插拔
+    pub fn event_sender(&self) -> tokio::sync::broadcast::Sender<DeviceEvent> {
+        self.events.clone()
+    }
+
     pub fn new() -> Result<Self, UHAError> {
         let mut devices = HashMap::new();
         
@@ -432,9 +1728,10 @@ impl VirtualHardwareAbstraction {
                     bandwidth: None,
                     specialized_units: None,
                 },
+                topology: None,
             },
         );
-        
+
         // 添加虚拟GPU
         devices.insert(
             "virtual-gpu-0".to_string(),
@@ -460,10 +1757,12 @@ impl VirtualHardwareAbstraction {
                     bandwidth: Some(100 * 1024 * 1024),
                     specialized_units: Some(1.0),
                 },
+                topology: None,
             },
         );
-        
-        Ok(Self { devices })
+
+        let (events, _) = tokio::sync::broadcast::channel(64);
+        Ok(Self { devices, events })
     }
 }
 
@@ -495,6 +1794,7 @@ impl HardwareAbstraction for VirtualHardwareAbstraction {
             used_bandwidth: Some(10 * 1024 * 1024), // 10MB/s
             temperature: Some(45.0), // 45°C
             power_usage: Some(75.0), // 75W
+            ..Default::default()
         })
     }
     
@@ -535,6 +1835,20 @@ impl HardwareAbstraction for VirtualHardwareAbstraction {
             network_bandwidth: 1000, // 1Gbps
             network_latency: 5,      // 5ms
             location: None,
+            numa_nodes: vec![nfa_common::types::NumaNodeInfo {
+                node_id: 0,
+                cpu_set: (0..16).collect(),
+                local_memory_bytes: 16 * 1024 * 1024 * 1024,
+            }],
         })
     }
+
+    async fn subscribe_device_events(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = DeviceEvent> + Send>>, UHAError> {
+        Ok(Box::pin(tokio_stream::wrappers::BroadcastStream::new(
+            self.events.subscribe(),
+        )
+        .filter_map(|r| r.ok())))
+    }
 }
\ No newline at end of file